@@ -3,6 +3,28 @@
 {{readme}}
 
 [`PreserveIgnoredFields`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.PreserveIgnoredFields.html
+[`PreserveIgnoredFieldsDeep`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.PreserveIgnoredFieldsDeep.html
+[`PreserveIgnoredFieldsBuffered`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.PreserveIgnoredFieldsBuffered.html
+[`PreserveIgnored`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/derive.PreserveIgnored.html
+[`preserve_ignored`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/attr.preserve_ignored.html
+[`FieldPath`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.FieldPath.html
+[`Map`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.Map.html
+[`Value`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/enum.Value.html
+[`FirstWins`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.FirstWins.html
+[`LastWins`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.LastWins.html
+[`CollectAll`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.CollectAll.html
+[`Limited`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.Limited.html
+[`DenyIgnoredFields`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.DenyIgnoredFields.html
+[`IgnoredFieldNames`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.IgnoredFieldNames.html
+[`WithMetadata`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.WithMetadata.html
+[`KeepOrder`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.KeepOrder.html
+[`ValueWins`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.ValueWins.html
+[`IgnoredWins`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.IgnoredWins.html
+[`AsRonStruct`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/struct.AsRonStruct.html
+[`JsonSchemaIgnoredFields`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/trait.JsonSchemaIgnoredFields.html
+[`JsonSchema1IgnoredFields`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/trait.JsonSchema1IgnoredFields.html
+[`DeserializeIgnoredFields`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/trait.DeserializeIgnoredFields.html
+[`SerializeIgnoredFields`]: https://docs.rs/serde-ignored-fields/latest/serde_ignored_fields/trait.SerializeIgnoredFields.html
 [`schemars::JsonSchema`]: https://docs.rs/schemars/latest/schemars/trait.JsonSchema.html
 [`serde`]: https://docs.rs/serde/
 [`serde::Deserializer`]: https://docs.rs/serde/latest/serde/trait.Deserializer.html
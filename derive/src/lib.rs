@@ -0,0 +1,252 @@
+//! Derive macro for the `serde-ignored-fields` crate.
+//!
+//! Do not use this crate directly.
+//! Enable the `derive` feature of `serde-ignored-fields` and use the re-exported macro instead.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Documented on the re-export in `serde-ignored-fields`, since rustdoc combines both.
+#[proc_macro_derive(PreserveIgnored, attributes(ignored_fields))]
+pub fn derive_preserve_ignored(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+	match expand(input) {
+		Ok(tokens) => tokens.into(),
+		Err(error) => error.into_compile_error().into(),
+	}
+}
+
+// Documented on the re-export in `serde-ignored-fields`, since rustdoc combines both.
+#[proc_macro_attribute]
+pub fn preserve_ignored(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+	if !args.is_empty() {
+		let args = TokenStream::from(args);
+		return syn::Error::new_spanned(args, "`#[preserve_ignored]` does not take arguments")
+			.into_compile_error()
+			.into();
+	}
+	match add_attributes(input) {
+		Ok(input) => quote!(#input).into(),
+		Err(error) => error.into_compile_error().into(),
+	}
+}
+
+/// Add the attributes needed by the derive macro to a struct, and the derive macro itself.
+fn add_attributes(mut input: syn::DeriveInput) -> syn::Result<syn::DeriveInput> {
+	let index = find_ignored_fields(&input)?.0;
+
+	if !has_serde_flag(&input.attrs, "remote")? {
+		input.attrs.push(syn::parse_quote!(#[serde(remote = "Self")]));
+	}
+	if !has_derive(&input.attrs, "PreserveIgnored")? {
+		input
+			.attrs
+			.push(syn::parse_quote!(#[derive(::serde_ignored_fields::PreserveIgnored)]));
+	}
+
+	if let syn::Data::Struct(syn::DataStruct {
+		fields: syn::Fields::Named(fields),
+		..
+	}) = &mut input.data
+	{
+		let field = &mut fields.named[index];
+		if !has_serde_flag(&field.attrs, "skip")? {
+			field.attrs.push(syn::parse_quote!(#[serde(skip)]));
+		}
+	}
+
+	Ok(input)
+}
+
+/// Generate the trait implementations for the derive macro.
+fn expand(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+	let (_, field) = find_ignored_fields(&input)?;
+
+	// Report all missing attributes at once.
+	let mut errors = Vec::new();
+	if !has_serde_flag(&input.attrs, "remote")? {
+		errors.push(syn::Error::new_spanned(
+			&input.ident,
+			"`PreserveIgnored` requires `#[serde(remote = \"Self\")]` on the struct, \
+			or use `#[serde_ignored_fields::preserve_ignored]` to add it automatically",
+		));
+	}
+	if !has_serde_flag(&field.attrs, "skip")? {
+		errors.push(syn::Error::new_spanned(
+			field,
+			"the `#[ignored_fields]` field requires `#[serde(skip)]`, \
+			or use `#[serde_ignored_fields::preserve_ignored]` on the struct to add it automatically",
+		));
+	}
+	if let Some(error) = errors.into_iter().reduce(|mut a, b| {
+		a.combine(b);
+		a
+	}) {
+		return Err(error);
+	}
+
+	let krate = quote!(::serde_ignored_fields);
+	let serde = quote!(#krate::__private::serde);
+	let ident = &input.ident;
+	let field_name = &field.ident;
+	let field_type = &field.ty;
+
+	let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+	let type_params: Vec<_> = input.generics.type_params().map(|x| &x.ident).collect();
+	let lifetimes: Vec<_> = input.generics.lifetimes().map(|x| &x.lifetime).collect();
+	let predicates = input.generics.where_clause.as_ref().map(|x| &x.predicates);
+
+	// Generics for the `Deserialize<'de>` implementations.
+	let mut de_generics = input.generics.clone();
+	if lifetimes.is_empty() {
+		de_generics.params.insert(0, syn::parse_quote!('de));
+	} else {
+		de_generics.params.insert(0, syn::parse_quote!('de: #(#lifetimes)+*));
+	}
+	let (de_impl_generics, _, _) = de_generics.split_for_impl();
+	let de_where = quote! {
+		where
+			#(#type_params: #serde::Deserialize<'de>,)*
+			#field_type: #krate::DeserializeIgnoredFields<'de>,
+			#predicates
+	};
+
+	// Generics for the borrowing wrapper used for serialization.
+	let mut ref_generics = input.generics.clone();
+	ref_generics.params.insert(0, syn::parse_quote!('__a));
+	let (ref_impl_generics, ref_ty_generics, _) = ref_generics.split_for_impl();
+	let ser_where = quote! {
+		where
+			#(#type_params: #serde::Serialize,)*
+			#field_type: #krate::SerializeIgnoredFields,
+			#predicates
+	};
+
+	Ok(quote! {
+		const _: () = {
+			/// Wrapper that uses the `Deserialize` implementation generated by `#[serde(remote = "Self")]`.
+			struct __Owned #impl_generics (#ident #ty_generics) where #predicates;
+
+			/// Wrapper that uses the `Serialize` implementation generated by `#[serde(remote = "Self")]`.
+			struct __Borrowed #ref_impl_generics (&'__a #ident #ty_generics) where #predicates;
+
+			impl #de_impl_generics #serde::Deserialize<'de> for __Owned #ty_generics #de_where {
+				fn deserialize<__D: #serde::Deserializer<'de>>(deserializer: __D) -> ::core::result::Result<Self, __D::Error> {
+					<#ident #ty_generics>::deserialize(deserializer).map(__Owned)
+				}
+			}
+
+			impl #ref_impl_generics #serde::Serialize for __Borrowed #ref_ty_generics #ser_where {
+				fn serialize<__S: #serde::Serializer>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error> {
+					<#ident #ty_generics>::serialize(self.0, serializer)
+				}
+			}
+
+			impl #de_impl_generics #serde::Deserialize<'de> for #ident #ty_generics #de_where {
+				fn deserialize<__D: #serde::Deserializer<'de>>(deserializer: __D) -> ::core::result::Result<Self, __D::Error> {
+					let wrapped: #krate::PreserveIgnoredFields<__Owned #ty_generics, #field_type> = #serde::Deserialize::deserialize(deserializer)?;
+					let mut value = wrapped.value.0;
+					value.#field_name = wrapped.ignored_fields;
+					::core::result::Result::Ok(value)
+				}
+			}
+
+			impl #impl_generics #serde::Serialize for #ident #ty_generics #ser_where {
+				fn serialize<__S: #serde::Serializer>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error> {
+					let wrapped = #krate::PreserveIgnoredFields::new(__Borrowed(self), &self.#field_name);
+					#serde::Serialize::serialize(&wrapped, serializer)
+				}
+			}
+		};
+	})
+}
+
+/// Find the field with the `#[ignored_fields]` attribute and its index.
+fn find_ignored_fields(input: &syn::DeriveInput) -> syn::Result<(usize, &syn::Field)> {
+	let fields = match &input.data {
+		syn::Data::Struct(syn::DataStruct {
+			fields: syn::Fields::Named(fields),
+			..
+		}) => &fields.named,
+		_ => {
+			return Err(syn::Error::new_spanned(
+				&input.ident,
+				"`PreserveIgnored` can only be derived for structs with named fields",
+			))
+		},
+	};
+
+	let mut found = None;
+	for (index, field) in fields.iter().enumerate() {
+		for attr in &field.attrs {
+			if !attr.path().is_ident("ignored_fields") {
+				continue;
+			}
+			attr.meta.require_path_only()?;
+			if found.is_some() {
+				return Err(syn::Error::new_spanned(
+					attr,
+					"only one field can have the `#[ignored_fields]` attribute",
+				));
+			}
+			found = Some((index, field));
+		}
+	}
+
+	found.ok_or_else(|| {
+		syn::Error::new_spanned(
+			&input.ident,
+			"`PreserveIgnored` requires a field with the `#[ignored_fields]` attribute",
+		)
+	})
+}
+
+/// Check if a `#[derive(...)]` attribute contains a derive macro with the given name.
+fn has_derive(attrs: &[syn::Attribute], name: &str) -> syn::Result<bool> {
+	for attr in attrs {
+		if !attr.path().is_ident("derive") {
+			continue;
+		}
+		let paths = attr.parse_args_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)?;
+		if paths
+			.iter()
+			.any(|path| path.segments.last().is_some_and(|x| x.ident == name))
+		{
+			return Ok(true);
+		}
+	}
+	Ok(false)
+}
+
+/// Check if a `#[serde(...)]` attribute contains the given flag.
+///
+/// For the `remote` flag, the value must be `"Self"`.
+fn has_serde_flag(attrs: &[syn::Attribute], flag: &str) -> syn::Result<bool> {
+	let mut found = false;
+	for attr in attrs {
+		if !attr.path().is_ident("serde") {
+			continue;
+		}
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident(flag) && flag == "remote" {
+				let value: syn::LitStr = meta.value()?.parse()?;
+				if value.value() != "Self" {
+					return Err(syn::Error::new_spanned(
+						value,
+						"`PreserveIgnored` requires `remote = \"Self\"`",
+					));
+				}
+				found = true;
+			} else if meta.path.is_ident(flag) {
+				found = true;
+			} else if meta.input.peek(syn::Token![=]) {
+				meta.value()?.parse::<syn::Expr>()?;
+			} else if meta.input.peek(syn::token::Paren) {
+				meta.input.parse::<proc_macro2::Group>()?;
+			}
+			Ok(())
+		})?;
+	}
+	Ok(found)
+}
@@ -0,0 +1,59 @@
+use crate::serialize::Serializer;
+use crate::value::{to_value, Map, Value, ValueDeserializer, ValueRefDeserializer};
+use crate::{DeserializeIgnoredFields, PreserveIgnoredFieldsBuffered, SerializeIgnoredFields};
+
+impl<'de, T, U> serde::de::Deserialize<'de> for PreserveIgnoredFieldsBuffered<T, U>
+where
+	T: serde::Deserialize<'de> + serde::Serialize,
+	U: DeserializeIgnoredFields<'de>,
+{
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::Error;
+
+		let human_readable = deserializer.is_human_readable();
+		let content = Value::deserialize(deserializer)?;
+		let value = T::deserialize(ValueRefDeserializer::<D::Error>::new(&content, human_readable))?;
+
+		let mut ignored_fields = U::default();
+		let entries = match content {
+			Value::Map(entries) if !entries.is_empty() => entries,
+			_ => return Ok(Self { value, ignored_fields }),
+		};
+
+		// Serialize the value again to find out which of the input fields it actually used.
+		let mut used = match to_value(&value, human_readable)
+			.map_err(D::Error::custom)?
+			.into_stripped()
+		{
+			Value::Map(used) => used,
+			_ => Map::new(),
+		};
+
+		for (key, value) in entries {
+			if used.find(&key).is_some() {
+				continue;
+			}
+			let key = ignored_fields.deserialize_key(ValueDeserializer::<D::Error>::new(key, human_readable))?;
+			let value = ignored_fields.deserialize_value(ValueDeserializer::<D::Error>::new(value, human_readable))?;
+			ignored_fields.insert(key, value)?;
+		}
+
+		Ok(Self { value, ignored_fields })
+	}
+}
+
+impl<T, U> serde::Serialize for PreserveIgnoredFieldsBuffered<T, U>
+where
+	T: serde::Serialize,
+	U: SerializeIgnoredFields,
+{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		// Untagged enums may have variants that are not maps, which can only be serialized without ignored fields.
+		if self.ignored_fields.is_empty() {
+			self.value.serialize(serializer)
+		} else {
+			self.value
+				.serialize(Serializer::new(serializer, &self.ignored_fields, &self.value))
+		}
+	}
+}
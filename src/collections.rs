@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+impl<'de, K, V> crate::DeserializeIgnoredFields<'de> for BTreeMap<K, V>
+where
+	K: serde::Deserialize<'de> + Ord + std::fmt::Debug,
+	V: serde::Deserialize<'de> + std::fmt::Debug,
+{
+	type Key = K;
+	type Value = V;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		use std::collections::btree_map::Entry;
+		match self.entry(key) {
+			Entry::Vacant(x) => {
+				x.insert(value);
+				Ok(())
+			},
+			Entry::Occupied(x) => Err(E::custom(format!("duplicate field: {:?}", x.key()))),
+		}
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		BTreeMap::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl<K, V> crate::SerializeIgnoredFields for BTreeMap<K, V>
+where
+	K: serde::Serialize,
+	V: serde::Serialize,
+{
+	type Key = K;
+	type Value = V;
+
+	fn len(&self) -> usize {
+		BTreeMap::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		BTreeMap::iter(self)
+	}
+}
+
+impl<'de, K, V, S> crate::DeserializeIgnoredFields<'de> for HashMap<K, V, S>
+where
+	K: serde::Deserialize<'de> + Eq + Hash + std::fmt::Debug,
+	V: serde::Deserialize<'de> + std::fmt::Debug,
+	S: BuildHasher + Default,
+{
+	type Key = K;
+	type Value = V;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		use std::collections::hash_map::Entry;
+		match self.entry(key) {
+			Entry::Vacant(x) => {
+				x.insert(value);
+				Ok(())
+			},
+			Entry::Occupied(x) => Err(E::custom(format!("duplicate field: {:?}", x.key()))),
+		}
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		HashMap::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl<K, V, S> crate::SerializeIgnoredFields for HashMap<K, V, S>
+where
+	K: serde::Serialize,
+	V: serde::Serialize,
+{
+	type Key = K;
+	type Value = V;
+
+	fn len(&self) -> usize {
+		HashMap::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		HashMap::iter(self)
+	}
+}
+
+/// Keeps the ignored fields in their original order, including duplicate keys.
+///
+/// Looking up a field with [`get_mut()`](crate::DeserializeIgnoredFields::get_mut) is a linear search.
+impl<'de, K, V> crate::DeserializeIgnoredFields<'de> for Vec<(K, V)>
+where
+	K: serde::Deserialize<'de> + PartialEq + std::fmt::Debug,
+	V: serde::Deserialize<'de> + std::fmt::Debug,
+{
+	type Key = K;
+	type Value = V;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		self.push((key, value));
+		Ok(())
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		self.iter_mut()
+			.find(|(existing, _)| existing == key)
+			.map(|(_, value)| value)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl<K, V> crate::SerializeIgnoredFields for Vec<(K, V)>
+where
+	K: serde::Serialize,
+	V: serde::Serialize,
+{
+	type Key = K;
+	type Value = V;
+
+	fn len(&self) -> usize {
+		Vec::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		self.as_slice().iter().map(|(key, value)| (key, value))
+	}
+}
+
+/// Collection that only records the names of ignored fields.
+///
+/// The values of the ignored fields are skipped with [`serde::de::IgnoredAny`], so they are never stored in memory.
+/// This is useful to report unknown fields, for example to warn about typos in a configuration file.
+/// The names are stored in the order they were encountered, including duplicates.
+///
+/// If the ignored fields belong to a struct, the names of the known fields of the struct are recorded too.
+/// You can use [`Self::suggestions()`] to find the known field that each ignored field was most likely meant to be.
+///
+/// By default, the names are stored as `String`.
+/// If the input can have fields with other keys, such as integers in YAML, you can use [`Value`](crate::Value) as key type instead.
+///
+/// Since the values are not stored, this type can not be used to serialize the ignored fields again.
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{IgnoredFieldNames, PreserveIgnoredFields};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///   hobby: String,
+/// }
+///
+/// let_assert!(Ok(config) = serde_yaml::from_str::<PreserveIgnoredFields<Config, IgnoredFieldNames>>("
+///   hobby: hair dressing
+///   hobyy: hair dressing
+///   colour: red
+/// "));
+/// assert!(config.ignored_fields.names == ["hobyy", "colour"]);
+///
+/// let suggestions: Vec<_> = config.ignored_fields.suggestions().collect();
+/// assert!(suggestions == [(&"hobyy".to_string(), Some("hobby")), (&"colour".to_string(), None)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoredFieldNames<K = String> {
+	/// The names of the ignored fields.
+	pub names: Vec<K>,
+
+	/// The names of the known fields, if the ignored fields belong to a struct.
+	pub known_fields: &'static [&'static str],
+}
+
+impl<K> Default for IgnoredFieldNames<K> {
+	fn default() -> Self {
+		Self {
+			names: Vec::new(),
+			known_fields: &[],
+		}
+	}
+}
+
+impl<K: AsRef<str>> IgnoredFieldNames<K> {
+	/// Pair each ignored field with the most similar known field, if there is one.
+	///
+	/// A known field is only suggested if it is similar enough to be a likely typo,
+	/// based on the number of characters that need to be changed to get from one name to the other.
+	pub fn suggestions(&self) -> impl Iterator<Item = (&K, Option<&'static str>)> {
+		self.names
+			.iter()
+			.map(|name| (name, crate::suggest::closest_field(name.as_ref(), self.known_fields)))
+	}
+}
+
+impl<'de, K> crate::DeserializeIgnoredFields<'de> for IgnoredFieldNames<K>
+where
+	K: serde::Deserialize<'de> + std::fmt::Debug,
+{
+	type Key = K;
+	type Value = serde::de::IgnoredAny;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, _value: Self::Value) -> Result<(), E> {
+		self.names.push(key);
+		Ok(())
+	}
+
+	fn set_known_fields(&mut self, fields: &'static [&'static str]) {
+		self.known_fields = fields;
+	}
+}
@@ -0,0 +1,91 @@
+use crate::forward::{forward_deserialize_ignored_fields, forward_serialize_ignored_fields};
+use crate::{DeserializeIgnoredFields, SerializeIgnoredFields};
+
+/// What to do when serializing a value that has a field with the same key as an ignored field.
+///
+/// This can happen if the ignored fields were modified, or if they were captured from a different type.
+/// Without a policy, the key would be written twice, which is invalid or ambiguous in most formats.
+///
+/// The policy is chosen by [`SerializeIgnoredFields::collision_policy()`].
+/// See [`ValueWins`] and [`IgnoredWins`] for wrappers that choose a policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CollisionPolicy {
+	/// Report an error.
+	#[default]
+	Error,
+
+	/// Keep the field of the value and skip the ignored field.
+	ValueWins,
+
+	/// Keep the ignored field and skip the field of the value.
+	IgnoredWins,
+}
+
+/// Wrapper for ignored fields that are skipped when the serialized value has a field with the same key.
+///
+/// It can be used with any type that implements [`DeserializeIgnoredFields`] or [`SerializeIgnoredFields`].
+/// The value is serialized an extra time to find the names of its fields before they are written.
+/// If the names can not be found, all ignored fields are added after the fields of the value,
+/// even if the wrapped type reports a different [`FieldPosition`](crate::FieldPosition) for them.
+///
+/// Without a wrapper, a collision is reported as an error.
+/// See also [`IgnoredWins`] and [`CollisionPolicy`].
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{PreserveIgnoredFields, ValueWins};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(serde::Serialize)]
+/// struct Thing {
+///   name: String,
+/// }
+///
+/// let thing = PreserveIgnoredFields {
+///   value: Thing { name: "Zohan".into() },
+///   ignored_fields: ValueWins(BTreeMap::from([("name", "Dahan"), ("colour", "red")])),
+/// };
+/// let_assert!(Ok(serialized) = serde_json::to_string(&thing));
+/// assert!(serialized == r#"{"name":"Zohan","colour":"red"}"#);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValueWins<U>(pub U);
+
+/// Wrapper for ignored fields that replace the field with the same key of the serialized value.
+///
+/// It can be used with any type that implements [`DeserializeIgnoredFields`] or [`SerializeIgnoredFields`].
+///
+/// Without a wrapper, a collision is reported as an error.
+/// See also [`ValueWins`] and [`CollisionPolicy`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IgnoredWins<U>(pub U);
+
+/// Implement [`DeserializeIgnoredFields`] and [`SerializeIgnoredFields`] for a wrapper that sets the collision policy.
+macro_rules! impl_collision_policy {
+	($($wrapper:ident => $policy:ident),* $(,)?) => {
+		$(
+			impl<'de, U: DeserializeIgnoredFields<'de>> DeserializeIgnoredFields<'de> for $wrapper<U> {
+				type Key = U::Key;
+				type Value = U::Value;
+
+				forward_deserialize_ignored_fields!(0);
+			}
+
+			impl<U: SerializeIgnoredFields> SerializeIgnoredFields for $wrapper<U> {
+				type Key = U::Key;
+				type Value = U::Value;
+
+				forward_serialize_ignored_fields!(0, except collision_policy);
+
+				fn collision_policy(&self) -> CollisionPolicy {
+					CollisionPolicy::$policy
+				}
+			}
+		)*
+	};
+}
+
+impl_collision_policy!(
+	ValueWins => ValueWins,
+	IgnoredWins => IgnoredWins,
+);
@@ -0,0 +1,866 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::deserialize::{CaptureFields, Wrap};
+#[cfg(feature = "ron")]
+use crate::features::ron::RonStruct;
+use crate::metadata::Metadata;
+use crate::path::{key_segment, FieldPath, PathSegment};
+#[cfg(feature = "ron")]
+use crate::serialize::ron_struct;
+use crate::serialize::{map_len, value_fields, FieldTracker, SerializeFields, StructFields};
+use crate::{DeserializeIgnoredFields, FieldPosition, PreserveIgnoredFieldsDeep, SerializeIgnoredFields};
+
+impl<'de, T, U> serde::de::Deserialize<'de> for PreserveIgnoredFieldsDeep<T, U>
+where
+	T: serde::Deserialize<'de>,
+	U: DeserializeIgnoredFields<'de>,
+{
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let mut ignored_fields = BTreeMap::new();
+		let value = T::deserialize(Wrap::new(deserializer, &mut DeepFields::new(&mut ignored_fields)))?;
+		Ok(Self { value, ignored_fields })
+	}
+}
+
+impl<T, U> serde::Serialize for PreserveIgnoredFieldsDeep<T, U>
+where
+	T: serde::Serialize,
+	U: SerializeIgnoredFields,
+{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		Nested::new(&self.value, &self.ignored_fields, Some(FieldPath::new())).serialize(serializer)
+	}
+}
+
+/// Collection of ignored fields for all nesting levels, used during deserialization.
+struct DeepFields<'a, U> {
+	/// The ignored fields per nesting level.
+	ignored_fields: &'a mut BTreeMap<FieldPath, U>,
+
+	/// The path of the current nesting level.
+	path: FieldPath,
+
+	/// The path of the nesting level whose ignored fields were used last.
+	last: Option<FieldPath>,
+
+	/// The metadata of the structs and enums at the current nesting level and its parents.
+	///
+	/// Metadata is only kept while a nesting level is being deserialized,
+	/// and it is only passed on to the ignored fields of the levels that have any.
+	metadata: Vec<Metadata>,
+}
+
+impl<'a, U> DeepFields<'a, U> {
+	/// Create a new collection that adds ignored fields to the given map.
+	fn new(ignored_fields: &'a mut BTreeMap<FieldPath, U>) -> Self {
+		Self {
+			ignored_fields,
+			path: FieldPath::new(),
+			last: None,
+			metadata: vec![Metadata::default()],
+		}
+	}
+
+	/// Get the metadata of the current nesting level.
+	fn metadata(&mut self) -> &mut Metadata {
+		// There is always one entry more than the number of path segments.
+		let index = self.path.len();
+		&mut self.metadata[index]
+	}
+
+	/// Get the ignored fields of the current nesting level, creating them if needed.
+	///
+	/// When switching to a different nesting level, its ignored fields continue from those of the previous level.
+	fn current<'de>(&mut self) -> &mut U
+	where
+		U: DeserializeIgnoredFields<'de>,
+	{
+		if self.last.as_ref() != Some(&self.path) {
+			let last = self.last.replace(self.path.clone());
+			if let Some((last, previous)) = last.and_then(|last| self.ignored_fields.remove_entry(&last)) {
+				self.level().continue_from(&previous);
+				self.ignored_fields.insert(last, previous);
+			}
+		}
+		self.level()
+	}
+
+	/// Get the ignored fields of the current nesting level, creating them if needed.
+	fn level<'de>(&mut self) -> &mut U
+	where
+		U: DeserializeIgnoredFields<'de>,
+	{
+		let metadata = &self.metadata[self.path.len()];
+		self.ignored_fields.entry(self.path.clone()).or_insert_with(|| {
+			let mut fields = U::default();
+			metadata.apply_to(&mut fields);
+			fields
+		})
+	}
+}
+
+impl<'a, 'de, U> CaptureFields<'de> for DeepFields<'a, U>
+where
+	U: DeserializeIgnoredFields<'de>,
+{
+	type Key = U::Key;
+	type Value = U::Value;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		self.current()
+			.insert(key, value)
+			.map_err(|e: E| E::custom(format_args!("{}{e}", PathPrefix(&self.path))))
+	}
+
+	fn deserialize_key<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Key, D::Error> {
+		self.current().deserialize_key(deserializer)
+	}
+
+	fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.current().deserialize_value(deserializer)
+	}
+
+	fn set_known_fields(&mut self, fields: &'static [&'static str]) {
+		self.metadata().fields = fields;
+		if let Some(ignored_fields) = self.ignored_fields.get_mut(&self.path) {
+			ignored_fields.set_known_fields(fields);
+		}
+	}
+
+	fn set_name(&mut self, name: &'static str) {
+		self.metadata().name = Some(name);
+		if let Some(ignored_fields) = self.ignored_fields.get_mut(&self.path) {
+			ignored_fields.set_name(name);
+		}
+	}
+
+	fn set_known_variants(&mut self, variants: &'static [&'static str]) {
+		self.metadata().variants = variants;
+		if let Some(ignored_fields) = self.ignored_fields.get_mut(&self.path) {
+			ignored_fields.set_known_variants(variants);
+		}
+	}
+
+	fn set_position(&mut self, position: FieldPosition<'_>) {
+		self.current().set_position(position)
+	}
+
+	fn enter(&mut self, segment: impl FnOnce() -> Option<PathSegment>) -> bool {
+		match segment() {
+			Some(segment) => {
+				self.path.push(segment);
+				self.metadata.push(Metadata::default());
+				true
+			},
+			None => false,
+		}
+	}
+
+	fn leave(&mut self) {
+		self.path.pop();
+		self.metadata.pop();
+	}
+
+	fn allow_any_map(&self) -> bool {
+		// Nested values like `serde_json::Value` also use `deserialize_any`, so only the top-level map is rejected.
+		!self.path.is_empty()
+	}
+}
+
+/// Check if there are ignored fields at or below a path.
+fn has_fields_below<U>(ignored_fields: &BTreeMap<FieldPath, U>, path: &FieldPath) -> bool {
+	// Paths sort directly after their prefixes, so we only need to check the first path that is not smaller.
+	ignored_fields
+		.range(path..)
+		.next()
+		.is_some_and(|(x, _)| x.starts_with(path))
+}
+
+/// A value to serialize with the ignored fields for a specific path.
+struct Nested<'a, T: ?Sized, U> {
+	/// The value to serialize.
+	value: &'a T,
+
+	/// The ignored fields of all nesting levels.
+	ignored_fields: &'a BTreeMap<FieldPath, U>,
+
+	/// The path of the value.
+	///
+	/// If the path is not known, the value is serialized without ignored fields.
+	path: Option<FieldPath>,
+}
+
+impl<'a, T: ?Sized, U> Nested<'a, T, U> {
+	/// Create a new nested value.
+	fn new(value: &'a T, ignored_fields: &'a BTreeMap<FieldPath, U>, path: Option<FieldPath>) -> Self {
+		Self {
+			value,
+			ignored_fields,
+			path,
+		}
+	}
+}
+
+impl<'a, T, U> serde::Serialize for Nested<'a, T, U>
+where
+	T: ?Sized + serde::Serialize,
+	U: SerializeIgnoredFields,
+{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match &self.path {
+			Some(path) if has_fields_below(self.ignored_fields, path) => self.value.serialize(Serializer::new(
+				serializer,
+				self.ignored_fields,
+				path.clone(),
+				self.value,
+			)),
+			_ => self.value.serialize(serializer),
+		}
+	}
+}
+
+/// Wrapper for a [`serde::Serializer`] to inject ignored fields in nested structs and maps.
+struct Serializer<'a, Inner, U, V: ?Sized> {
+	/// The wrapped serializer.
+	inner: Inner,
+
+	/// The ignored fields of all nesting levels.
+	ignored_fields: &'a BTreeMap<FieldPath, U>,
+
+	/// The path of the value being serialized.
+	path: FieldPath,
+
+	/// The value being serialized.
+	///
+	/// It is serialized again to find the names of its fields before they are serialized,
+	/// and as the body of a struct variant with ignored fields.
+	value: &'a V,
+
+	/// If true, the value is being serialized as the body of a newtype variant, so a struct variant is written as a map.
+	variant_body: bool,
+}
+
+impl<'a, Inner, U, V: ?Sized> Serializer<'a, Inner, U, V> {
+	/// Wrap a serializer.
+	fn new(inner: Inner, ignored_fields: &'a BTreeMap<FieldPath, U>, path: FieldPath, value: &'a V) -> Self {
+		Self {
+			inner,
+			ignored_fields,
+			path,
+			value,
+			variant_body: false,
+		}
+	}
+
+	/// Get the ignored fields at the path of the value being serialized.
+	fn own_fields(&self) -> Option<&'a U> {
+		self.ignored_fields.get(&self.path)
+	}
+}
+
+impl<'a, Inner, U: SerializeIgnoredFields, V: ?Sized> Serializer<'a, Inner, U, V> {
+	/// Check that there are no ignored fields at the path of a value that is not a map or struct.
+	///
+	/// The ignored fields can not be added to such a value, so they would be lost.
+	fn check_no_fields<E: serde::ser::Error>(&self, kind: &str) -> Result<(), E> {
+		match self.own_fields() {
+			Some(fields) if !fields.is_empty() => Err(E::custom(format_args!(
+				"{}invalid type `{kind}`: can only re-serialize a map or struct with ignored fields",
+				PathPrefix(&self.path),
+			))),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Display a path followed by `: `, or nothing for the empty path.
+struct PathPrefix<'a>(&'a FieldPath);
+
+impl std::fmt::Display for PathPrefix<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.0.is_empty() {
+			Ok(())
+		} else {
+			write!(f, "{}: ", self.0)
+		}
+	}
+}
+
+/// Forward serialize functions for primitive types to `self.inner`.
+///
+/// Fails if there are ignored fields for the path of the value.
+macro_rules! forward_serializer {
+	($(($ident:ident, $type:ty, $kind:literal))*) => {
+		$(
+			fn $ident(self, v: $type) -> Result<Self::Ok, Self::Error> {
+				self.check_no_fields($kind)?;
+				self.inner.$ident(v)
+			}
+		)*
+	};
+}
+
+impl<'a, S, U, V> serde::Serializer for Serializer<'a, S, U, V>
+where
+	S: serde::Serializer,
+	U: SerializeIgnoredFields,
+	V: ?Sized + serde::Serialize,
+{
+	type Error = S::Error;
+	type Ok = S::Ok;
+	type SerializeMap = Compound<'a, S::SerializeMap, U>;
+	type SerializeSeq = Compound<'a, S::SerializeSeq, U>;
+	type SerializeStruct = Struct<'a, S, U>;
+	type SerializeStructVariant = StructVariant<'a, S, U>;
+	type SerializeTuple = Compound<'a, S::SerializeTuple, U>;
+	type SerializeTupleStruct = Compound<'a, S::SerializeTupleStruct, U>;
+	type SerializeTupleVariant = Compound<'a, S::SerializeTupleVariant, U>;
+
+	forward_serializer!(
+		(serialize_bool, bool, "bool")
+		(serialize_i8, i8, "i8")
+		(serialize_i16, i16, "i16")
+		(serialize_i32, i32, "i32")
+		(serialize_i64, i64, "i64")
+		(serialize_i128, i128, "i128")
+		(serialize_u8, u8, "u8")
+		(serialize_u16, u16, "u16")
+		(serialize_u32, u32, "u32")
+		(serialize_u64, u64, "u64")
+		(serialize_u128, u128, "u128")
+		(serialize_f32, f32, "f32")
+		(serialize_f64, f64, "f64")
+		(serialize_char, char, "char")
+		(serialize_str, &str, "str")
+		(serialize_bytes, &[u8], "bytes")
+		(serialize_unit_struct, &'static str, "unit struct")
+	);
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		self.check_no_fields("none")?;
+		self.inner.serialize_none()
+	}
+
+	fn serialize_some<T: ?Sized + serde::Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		self.inner
+			.serialize_some(&Nested::new(value, self.ignored_fields, Some(self.path)))
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		self.check_no_fields("unit")?;
+		self.inner.serialize_unit()
+	}
+
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Self::Error> {
+		self.check_no_fields("unit variant")?;
+		self.inner.serialize_unit_variant(name, variant_index, variant)
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
+		self,
+		name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		self.inner
+			.serialize_newtype_struct(name, &Nested::new(value, self.ignored_fields, Some(self.path)))
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		self.inner.serialize_newtype_variant(
+			name,
+			variant_index,
+			variant,
+			&Nested::new(value, self.ignored_fields, Some(self.path)),
+		)
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		self.check_no_fields("sequence")?;
+		let seq = self.inner.serialize_seq(len)?;
+		Ok(Compound::new(seq, self.ignored_fields, self.path))
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		self.check_no_fields("tuple")?;
+		let tuple = self.inner.serialize_tuple(len)?;
+		Ok(Compound::new(tuple, self.ignored_fields, self.path))
+	}
+
+	fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.check_no_fields("tuple")?;
+		let tuple = self.inner.serialize_tuple_struct(name, len)?;
+		Ok(Compound::new(tuple, self.ignored_fields, self.path))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		self.check_no_fields("tuple variant")?;
+		let tuple = self.inner.serialize_tuple_variant(name, variant_index, variant, len)?;
+		Ok(Compound::new(tuple, self.ignored_fields, self.path))
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		let (len, value_fields) = match self.own_fields() {
+			Some(fields) => {
+				let value_fields = value_fields(self.value, fields);
+				(map_len(len, fields, value_fields.as_ref()), value_fields)
+			},
+			None => (len, None),
+		};
+		let map = self.inner.serialize_map(len)?;
+		Compound::start_map(map, self.ignored_fields, self.path, value_fields.as_ref())
+	}
+
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		// Only turn the struct into a map if we actually need to add fields.
+		let fields = match self.own_fields() {
+			Some(fields) if !fields.is_empty() => fields,
+			_ => {
+				let ser = self.inner.serialize_struct(name, len)?;
+				return Ok(Struct::Plain(Compound::start_struct(
+					StructFields(ser),
+					self.ignored_fields,
+					self.path,
+					None,
+				)?));
+			},
+		};
+		let value_fields = value_fields(self.value, fields);
+		#[cfg(feature = "ron")]
+		if ron_struct(fields) {
+			let ser = RonStruct::new(self.inner, None);
+			return Ok(Struct::Ron(Compound::start_struct(
+				ser,
+				self.ignored_fields,
+				self.path,
+				value_fields.as_ref(),
+			)?));
+		}
+		let map = self
+			.inner
+			.serialize_map(map_len(Some(len), fields, value_fields.as_ref()))?;
+		Ok(Struct::Map(Compound::start_map(
+			map,
+			self.ignored_fields,
+			self.path,
+			value_fields.as_ref(),
+		)?))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		// Only buffer the struct variant if we actually need to add fields.
+		let fields = match self.own_fields() {
+			Some(fields) if !fields.is_empty() => fields,
+			_ => {
+				let ser = self.inner.serialize_struct_variant(name, variant_index, variant, len)?;
+				return Ok(StructVariant::Variant(Compound::new(
+					ser,
+					self.ignored_fields,
+					self.path,
+				)));
+			},
+		};
+		let value_fields = value_fields(self.value, fields);
+		#[cfg(feature = "ron")]
+		if ron_struct(fields) {
+			let ser = RonStruct::new(self.inner, Some(variant));
+			return Ok(StructVariant::Ron(Compound::start_struct(
+				ser,
+				self.ignored_fields,
+				self.path,
+				value_fields.as_ref(),
+			)?));
+		}
+		if self.variant_body {
+			let map = self
+				.inner
+				.serialize_map(map_len(Some(len), fields, value_fields.as_ref()))?;
+			return Ok(StructVariant::Map(Compound::start_map(
+				map,
+				self.ignored_fields,
+				self.path,
+				value_fields.as_ref(),
+			)?));
+		}
+		let body = VariantBody {
+			value: self.value,
+			ignored_fields: self.ignored_fields,
+			path: self.path,
+		};
+		let ok = self
+			.inner
+			.serialize_newtype_variant(name, variant_index, variant, &body)?;
+		Ok(StructVariant::Done(ok))
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.inner.is_human_readable()
+	}
+}
+
+/// Wrapper for compound serializers to serialize the elements with the ignored fields for their path.
+///
+/// When used for a map or struct, the ignored fields for the path of the map or struct are added to it.
+struct Compound<'a, Inner, U: SerializeIgnoredFields> {
+	/// The wrapped compound serializer.
+	inner: Inner,
+
+	/// The ignored fields of all nesting levels.
+	ignored_fields: &'a BTreeMap<FieldPath, U>,
+
+	/// The path of the compound value.
+	path: FieldPath,
+
+	/// The index of the next element of a sequence or tuple.
+	index: usize,
+
+	/// The path segment of the last serialized map key.
+	key: Option<PathSegment>,
+
+	/// The tracker for the fields of a map that the ignored fields for its path are added to.
+	tracker: FieldTracker<'a, U>,
+}
+
+impl<'a, Inner, U: SerializeIgnoredFields> Compound<'a, Inner, U> {
+	/// Wrap a compound serializer.
+	fn new(inner: Inner, ignored_fields: &'a BTreeMap<FieldPath, U>, path: FieldPath) -> Self {
+		Self {
+			inner,
+			ignored_fields,
+			tracker: FieldTracker::new(ignored_fields.get(&path)),
+			path,
+			index: 0,
+			key: None,
+		}
+	}
+
+	/// Wrap the next element of a sequence or tuple.
+	fn element<'b, T: ?Sized>(&mut self, value: &'b T) -> Nested<'b, T, U>
+	where
+		'a: 'b,
+	{
+		let path = self.path.join(PathSegment::Index(self.index));
+		self.index += 1;
+		Nested::new(value, self.ignored_fields, Some(path))
+	}
+
+	/// Wrap a field value.
+	///
+	/// If the key can not be used as path segment, the ignored fields of the value are not added.
+	fn field<'b, T: ?Sized>(&self, key: Option<PathSegment>, value: &'b T) -> Nested<'b, T, U>
+	where
+		'a: 'b,
+	{
+		let path = key.map(|key| self.path.join(key));
+		Nested::new(value, self.ignored_fields, path)
+	}
+}
+
+impl<'a, M, U> Compound<'a, M, U>
+where
+	M: serde::ser::SerializeMap,
+	U: SerializeIgnoredFields,
+{
+	/// Wrap a map serializer and add the ignored fields that belong at the start of it.
+	///
+	/// The names of the fields of the value are used to handle collisions in advance, if they are known.
+	fn start_map(
+		inner: M,
+		ignored_fields: &'a BTreeMap<FieldPath, U>,
+		path: FieldPath,
+		value_fields: Option<&BTreeSet<String>>,
+	) -> Result<Self, M::Error> {
+		let mut map = Self::new(inner, ignored_fields, path);
+		map.tracker.start(&mut map.inner, value_fields)?;
+		Ok(map)
+	}
+
+	/// Add a field to the map, followed by the ignored fields that belong after it.
+	fn entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), M::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize,
+	{
+		let value = self.field(key_segment(key), value);
+		self.tracker.entry(&mut self.inner, key, &value)
+	}
+
+	/// Add the remaining ignored fields for the path of the map and end it.
+	fn end_map(mut self) -> Result<M::Ok, M::Error> {
+		self.tracker.end(&mut self.inner)?;
+		self.inner.end()
+	}
+}
+
+impl<'a, St, U> Compound<'a, St, U>
+where
+	St: SerializeFields + serde::ser::SerializeStruct<Error = <St as SerializeFields>::Error>,
+	U: SerializeIgnoredFields,
+{
+	/// Wrap a struct serializer and add the ignored fields that belong at the start of it.
+	///
+	/// The names of the fields of the value are used to handle collisions in advance, if they are known.
+	fn start_struct(
+		inner: St,
+		ignored_fields: &'a BTreeMap<FieldPath, U>,
+		path: FieldPath,
+		value_fields: Option<&BTreeSet<String>>,
+	) -> Result<Self, <St as SerializeFields>::Error> {
+		let mut ser = Self::new(inner, ignored_fields, path);
+		ser.tracker.start(&mut ser.inner, value_fields)?;
+		Ok(ser)
+	}
+
+	/// Add a field to the struct, followed by the ignored fields that belong after it.
+	fn struct_field<V: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &V,
+	) -> Result<(), <St as SerializeFields>::Error> {
+		let value = self.field(Some(key.into()), value);
+		self.tracker.field(&mut self.inner, key, &value)
+	}
+
+	/// Add the remaining ignored fields for the path of the struct and end it.
+	fn end_struct(mut self) -> Result<St::Ok, <St as SerializeFields>::Error> {
+		self.tracker.end(&mut self.inner)?;
+		self.inner.end()
+	}
+}
+
+/// Implement a compound serializer trait for [`Compound`] by forwarding each element to `self.inner`.
+macro_rules! impl_compound_elements {
+	($($trait:ident::$method:ident),* $(,)?) => {
+		$(
+			impl<'a, M, U> serde::ser::$trait for Compound<'a, M, U>
+			where
+				M: serde::ser::$trait,
+				U: SerializeIgnoredFields,
+			{
+				type Error = M::Error;
+				type Ok = M::Ok;
+
+				fn $method<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+					let value = self.element(value);
+					self.inner.$method(&value)
+				}
+
+				fn end(self) -> Result<Self::Ok, Self::Error> {
+					self.inner.end()
+				}
+			}
+		)*
+	};
+}
+
+impl_compound_elements!(
+	SerializeSeq::serialize_element,
+	SerializeTuple::serialize_element,
+	SerializeTupleStruct::serialize_field,
+	SerializeTupleVariant::serialize_field,
+);
+
+impl<'a, M, U> serde::ser::SerializeMap for Compound<'a, M, U>
+where
+	M: serde::ser::SerializeMap,
+	U: SerializeIgnoredFields,
+{
+	type Error = M::Error;
+	type Ok = M::Ok;
+
+	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.key = key_segment(key);
+		if self.tracker.key(key)? {
+			self.inner.serialize_key(key)?;
+		}
+		Ok(())
+	}
+
+	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		let key = self.key.take();
+		let value = self.field(key, value);
+		self.tracker.value(&mut self.inner, &value)
+	}
+
+	fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize,
+	{
+		self.entry(key, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end_map()
+	}
+}
+
+/// The body of a struct variant with ignored fields, serialized as a map.
+struct VariantBody<'a, V: ?Sized, U> {
+	/// The value that serializes as the struct variant.
+	value: &'a V,
+
+	/// The ignored fields of all nesting levels.
+	ignored_fields: &'a BTreeMap<FieldPath, U>,
+
+	/// The path of the struct variant.
+	path: FieldPath,
+}
+
+impl<'a, V, U> serde::Serialize for VariantBody<'a, V, U>
+where
+	V: ?Sized + serde::Serialize,
+	U: SerializeIgnoredFields,
+{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		// Serialize the value again, but write the struct variant as a map this time.
+		let serializer = Serializer {
+			variant_body: true,
+			..Serializer::new(serializer, self.ignored_fields, self.path.clone(), self.value)
+		};
+		self.value.serialize(serializer)
+	}
+}
+
+/// Serializer for a struct variant that is serialized directly or as a newtype variant containing a map.
+///
+/// Struct variants are only serialized as a newtype variant if there are ignored fields to add to them.
+enum StructVariant<'a, S: serde::Serializer, U: SerializeIgnoredFields> {
+	/// Serialize as a struct variant.
+	Variant(Compound<'a, S::SerializeStructVariant, U>),
+
+	/// The variant was already serialized as a newtype variant by serializing the value again.
+	///
+	/// The fields passed to this serializer are ignored.
+	Done(S::Ok),
+
+	/// Serialize as a map, as the body of a newtype variant.
+	Map(Compound<'a, S::SerializeMap, U>),
+
+	/// Serialize as RON struct variant.
+	#[cfg(feature = "ron")]
+	Ron(Compound<'a, RonStruct<S>, U>),
+}
+
+impl<'a, S, U> serde::ser::SerializeStructVariant for StructVariant<'a, S, U>
+where
+	S: serde::Serializer,
+	U: SerializeIgnoredFields,
+{
+	type Error = S::Error;
+	type Ok = S::Ok;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		match self {
+			Self::Variant(ser) => {
+				let value = ser.field(Some(key.into()), value);
+				ser.inner.serialize_field(key, &value)
+			},
+			Self::Done(_) => Ok(()),
+			Self::Map(map) => map.entry(key, value),
+			#[cfg(feature = "ron")]
+			Self::Ron(ser) => ser.struct_field(key, value),
+		}
+	}
+
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+		match self {
+			Self::Variant(ser) => ser.inner.skip_field(key),
+			Self::Done(_) | Self::Map(_) => Ok(()),
+			#[cfg(feature = "ron")]
+			Self::Ron(_) => Ok(()),
+		}
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		match self {
+			Self::Variant(ser) => ser.inner.end(),
+			Self::Done(ok) => Ok(ok),
+			Self::Map(map) => map.end_map(),
+			#[cfg(feature = "ron")]
+			Self::Ron(ser) => ser.end_struct(),
+		}
+	}
+}
+
+/// Serializer for a struct that is serialized as a struct or as a map.
+///
+/// Structs are only serialized as a map if there are ignored fields to add to them.
+/// RON structs with ignored fields in [`AsRonStruct`](crate::AsRonStruct) are written by [`RonStruct`] instead.
+enum Struct<'a, S: serde::Serializer, U: SerializeIgnoredFields> {
+	/// Serialize as a struct.
+	Plain(Compound<'a, StructFields<S::SerializeStruct>, U>),
+
+	/// Serialize as a map.
+	Map(Compound<'a, S::SerializeMap, U>),
+
+	/// Serialize as RON struct.
+	#[cfg(feature = "ron")]
+	Ron(Compound<'a, RonStruct<S>, U>),
+}
+
+impl<'a, S, U> serde::ser::SerializeStruct for Struct<'a, S, U>
+where
+	S: serde::Serializer,
+	U: SerializeIgnoredFields,
+{
+	type Error = S::Error;
+	type Ok = S::Ok;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		match self {
+			Self::Plain(ser) => ser.struct_field(key, value),
+			Self::Map(map) => map.entry(key, value),
+			#[cfg(feature = "ron")]
+			Self::Ron(ser) => ser.struct_field(key, value),
+		}
+	}
+
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+		match self {
+			Self::Plain(ser) => ser.inner.skip_field(key),
+			Self::Map(_) => Ok(()),
+			#[cfg(feature = "ron")]
+			Self::Ron(_) => Ok(()),
+		}
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		match self {
+			Self::Plain(ser) => ser.end_struct(),
+			Self::Map(map) => map.end_map(),
+			#[cfg(feature = "ron")]
+			Self::Ron(ser) => ser.end_struct(),
+		}
+	}
+}
@@ -0,0 +1,72 @@
+use crate::deserialize::Wrap;
+use crate::suggest::closest_field;
+use crate::value::Value;
+use crate::{DenyIgnoredFields, IgnoredFieldNames};
+
+impl<'de, T> serde::Deserialize<'de> for DenyIgnoredFields<T>
+where
+	T: serde::Deserialize<'de>,
+{
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::Error;
+
+		let mut unknown = IgnoredFieldNames::<Value>::default();
+		let value = T::deserialize(Wrap::new(deserializer, &mut unknown))?;
+		if unknown.names.is_empty() {
+			Ok(Self { value })
+		} else {
+			Err(D::Error::custom(UnknownFields(&unknown)))
+		}
+	}
+}
+
+impl<T: serde::Serialize> serde::Serialize for DenyIgnoredFields<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.value.serialize(serializer)
+	}
+}
+
+/// Display the keys of all ignored fields in an error message.
+struct UnknownFields<'a>(&'a IgnoredFieldNames<Value>);
+
+impl std::fmt::Display for UnknownFields<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let names = &self.0.names;
+		if names.len() == 1 {
+			write!(f, "unknown field ")?;
+		} else {
+			write!(f, "unknown fields: ")?;
+		}
+		for (i, name) in names.iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+			write!(f, "{}", KeyName(name))?;
+			let suggestion = name.as_str().and_then(|name| closest_field(name, self.0.known_fields));
+			if let Some(suggestion) = suggestion {
+				write!(f, " (did you mean `{suggestion}`?)")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Display a key of an ignored field in an error message.
+struct KeyName<'a>(&'a Value);
+
+impl std::fmt::Display for KeyName<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if let Some((negative, magnitude)) = self.0.as_integer() {
+			let sign = if negative { "-" } else { "" };
+			return write!(f, "`{sign}{magnitude}`");
+		}
+		match self.0.strip() {
+			Value::String(x) => write!(f, "`{x}`"),
+			Value::Char(x) => write!(f, "`{x}`"),
+			Value::Bool(x) => write!(f, "`{x}`"),
+			Value::F32(x) => write!(f, "`{x}`"),
+			Value::F64(x) => write!(f, "`{x}`"),
+			x => write!(f, "{x:?}"),
+		}
+	}
+}
@@ -1,5 +1,6 @@
 use crate::key::Key;
-use crate::{DeserializeIgnoredFields, PreserveIgnoredFields};
+use crate::path::PathSegment;
+use crate::{DeserializeIgnoredFields, FieldPosition, PreserveIgnoredFields};
 
 impl<'de, T, U> serde::de::Deserialize<'de> for PreserveIgnoredFields<T, U>
 where
@@ -13,23 +14,133 @@ where
 	}
 }
 
-/// Wrapper for a [`serde::Deserializer`] or [`serde::Visitor`] to preserve ignored fields of a map.
-struct Wrap<'a, Inner, IgnoredFields> {
+/// Collection that ignored fields are added to during deserialization.
+///
+/// Implemented for all [`DeserializeIgnoredFields`] types,
+/// which only capture the ignored fields of the top-level map.
+/// Collections that also capture the ignored fields of nested maps can enter and leave nested values.
+pub(crate) trait CaptureFields<'de> {
+	/// The type of the key for the ignored fields.
+	type Key: serde::Deserialize<'de>;
+
+	/// The type of the value of ignored fields.
+	type Value: serde::Deserialize<'de>;
+
+	/// Insert an ignored field at the current nesting level.
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E>;
+
+	/// Deserialize the key of an ignored field at the current nesting level.
+	fn deserialize_key<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Key, D::Error>;
+
+	/// Deserialize the value of an ignored field at the current nesting level.
+	fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error>;
+
+	/// Set the names of the known fields of the struct at the current nesting level.
+	fn set_known_fields(&mut self, fields: &'static [&'static str]);
+
+	/// Set the name of the struct or enum at the current nesting level.
+	fn set_name(&mut self, name: &'static str);
+
+	/// Set the names of the variants of the enum at the current nesting level.
+	fn set_known_variants(&mut self, variants: &'static [&'static str]);
+
+	/// Set the position of the next ignored field at the current nesting level.
+	fn set_position(&mut self, position: FieldPosition<'_>);
+
+	/// Enter a nested value to capture the ignored fields inside of it.
+	///
+	/// Returns `false` if the ignored fields of the nested value should not be captured.
+	/// The `segment` function is only called if nested values are captured at all.
+	fn enter(&mut self, segment: impl FnOnce() -> Option<PathSegment>) -> bool {
+		let _ = segment;
+		false
+	}
+
+	/// Leave a nested value that was entered with [`Self::enter()`].
+	fn leave(&mut self) {}
+
+	/// Check if a map that is deserialized with [`serde::Deserializer::deserialize_any()`] may be passed on at the current nesting level.
+	///
+	/// Untagged and internally tagged enums buffer a map with `deserialize_any` before picking a variant,
+	/// so the fields that the variant ignores never reach [`Self::insert()`].
+	/// Returns `false` if such a map must be rejected instead of silently losing its ignored fields.
+	fn allow_any_map(&self) -> bool {
+		false
+	}
+}
+
+impl<'de, U: DeserializeIgnoredFields<'de>> CaptureFields<'de> for U {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		DeserializeIgnoredFields::insert(self, key, value)
+	}
+
+	fn deserialize_key<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Key, D::Error> {
+		DeserializeIgnoredFields::deserialize_key(self, deserializer)
+	}
+
+	fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error> {
+		DeserializeIgnoredFields::deserialize_value(self, deserializer)
+	}
+
+	fn set_known_fields(&mut self, fields: &'static [&'static str]) {
+		DeserializeIgnoredFields::set_known_fields(self, fields)
+	}
+
+	fn set_name(&mut self, name: &'static str) {
+		DeserializeIgnoredFields::set_name(self, name)
+	}
+
+	fn set_known_variants(&mut self, variants: &'static [&'static str]) {
+		DeserializeIgnoredFields::set_known_variants(self, variants)
+	}
+
+	fn set_position(&mut self, position: FieldPosition<'_>) {
+		DeserializeIgnoredFields::set_position(self, position)
+	}
+}
+
+/// Wrapper for a [`serde::Deserializer`], [`serde::de::Visitor`] or [`serde::de::DeserializeSeed`] to preserve ignored fields of a map.
+pub(crate) struct Wrap<'a, Inner, IgnoredFields> {
 	/// The wrapped [`serde::Deserializer`] or [`serde::de::Visitor`].
 	inner: Inner,
 
 	/// Collection to add ignored fields into.
 	ignored_fields: &'a mut IgnoredFields,
+
+	/// If true, the wrapped [`serde::de::Visitor`] was passed to [`serde::Deserializer::deserialize_any()`].
+	any: bool,
+
+	/// If true, the wrapped [`serde::de::Visitor`] deserializes a struct, so the keys of a map are field names.
+	is_struct: bool,
 }
 
 impl<'a, Inner, IgnoredFields> Wrap<'a, Inner, IgnoredFields> {
-	/// Wrapper for a [`serde::Deserializer`] or [`serde::Visitor`].
-	fn new(inner: Inner, ignored_fields: &'a mut IgnoredFields) -> Self {
-		Self { inner, ignored_fields }
+	/// Wrapper for a [`serde::Deserializer`], [`serde::de::Visitor`] or [`serde::de::DeserializeSeed`].
+	pub(crate) fn new(inner: Inner, ignored_fields: &'a mut IgnoredFields) -> Self {
+		Self {
+			inner,
+			ignored_fields,
+			any: false,
+			is_struct: false,
+		}
+	}
+
+	/// Wrapper for the [`serde::de::Visitor`] of a struct or struct variant.
+	fn for_struct(inner: Inner, ignored_fields: &'a mut IgnoredFields) -> Self {
+		Self {
+			is_struct: true,
+			..Self::new(inner, ignored_fields)
+		}
 	}
 }
 
 /// Forward deserialize implementations to `self.inner` with some glue code.
+///
+/// Instead of glue code, you can specify `nested` to enter a nested value for the ignored fields
+/// and wrap the visitor if the ignored fields of nested values are captured.
 macro_rules! forward_deserializer {
 	(fn deserialize($self:ident, $visitor:ident) $pre:tt for [$($ident:ident),* $(,)?]) => {
 		$(
@@ -68,6 +179,19 @@ macro_rules! forward_deserializer {
 	(@map_ident identifier     ($self:ident, $visitor:ident) $pre:tt) => { forward_deserializer!(@ fn deserialize_identifier      ($self, $visitor) $pre); };
 	(@map_ident ignored_any    ($self:ident, $visitor:ident) $pre:tt) => { forward_deserializer!(@ fn deserialize_ignored_any     ($self, $visitor) $pre); };
 
+	(@ fn $ident:ident($self:ident, $visitor:ident) $(( $($arg_name:ident: $arg_type:ty),* $(,)?))? nested) => {
+		fn $ident<V: serde::de::Visitor<'de>>($self, $($($arg_name: $arg_type,)*)? $visitor: V) -> Result<V::Value, Self::Error> {
+			let ignored_fields = $self.ignored_fields;
+			let key = $self.key;
+			if ignored_fields.enter(|| key.as_ref().and_then(Key::to_path_segment)) {
+				let result = $self.inner.$ident($($($arg_name,)*)? Wrap::new($visitor, &mut *ignored_fields));
+				ignored_fields.leave();
+				result
+			} else {
+				$self.inner.$ident($($($arg_name,)*)? $visitor)
+			}
+		}
+	};
 	(@ fn $ident:ident($self:ident, $visitor:ident) $(( $($arg_name:ident: $arg_type:ty),* $(,)?))? { $($pre:tt)* }) => {
 		fn $ident<V: serde::de::Visitor<'de>>($self, $($($arg_name: $arg_type,)*)? $visitor: V) -> Result<V::Value, Self::Error> {
 			$($pre)*
@@ -79,7 +203,7 @@ macro_rules! forward_deserializer {
 impl<'a, 'de, D, IgnoredFields> serde::de::Deserializer<'de> for Wrap<'a, D, IgnoredFields>
 where
 	D: serde::de::Deserializer<'de>,
-	IgnoredFields: DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	type Error = D::Error;
 
@@ -88,7 +212,6 @@ where
 			let visitor = Wrap::new(visitor, self.ignored_fields);
 		}
 		for [
-			any,
 			bool,
 			i8,
 			i16,
@@ -115,35 +238,144 @@ where
 			tuple,
 			tuple_struct,
 			map,
-			struct,
-			enum,
 			identifier,
 			ignored_any,
 		]
 	);
+
+	fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let visitor = Wrap {
+			inner: visitor,
+			ignored_fields: self.ignored_fields,
+			any: true,
+			is_struct: false,
+		};
+		self.inner.deserialize_any(visitor)
+	}
+
+	fn deserialize_struct<V: serde::de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.ignored_fields.set_name(name);
+		self.ignored_fields.set_known_fields(fields);
+		self.inner
+			.deserialize_struct(name, fields, Wrap::for_struct(visitor, self.ignored_fields))
+	}
+
+	fn deserialize_enum<V: serde::de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.ignored_fields.set_name(name);
+		self.ignored_fields.set_known_variants(variants);
+		self.inner
+			.deserialize_enum(name, variants, Wrap::new(visitor, self.ignored_fields))
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.inner.is_human_readable()
+	}
+}
+
+impl<'a, 'de, Seed, IgnoredFields> serde::de::DeserializeSeed<'de> for Wrap<'a, Seed, IgnoredFields>
+where
+	Seed: serde::de::DeserializeSeed<'de>,
+	IgnoredFields: CaptureFields<'de>,
+{
+	type Value = Seed::Value;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.inner.deserialize(Wrap::new(deserializer, self.ignored_fields))
+	}
+}
+
+/// Macro to forward visitor functions to `self.inner`.
+macro_rules! forward_visitor_plain {
+	($(($ident:ident, $type:ty))*) => {
+		$(
+			fn $ident<E: serde::de::Error>(self, value: $type) -> Result<Self::Value, E> {
+				self.inner.$ident(value)
+			}
+		)*
+	};
 }
 
 impl<'a, 'de, V, IgnoredFields> serde::de::Visitor<'de> for Wrap<'a, V, IgnoredFields>
 where
 	V: serde::de::Visitor<'de>,
-	IgnoredFields: DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	type Value = V::Value;
 
+	forward_visitor_plain!(
+		(visit_bool, bool)
+		(visit_i8, i8)
+		(visit_i16, i16)
+		(visit_i32, i32)
+		(visit_i64, i64)
+		(visit_i128, i128)
+		(visit_u8, u8)
+		(visit_u16, u16)
+		(visit_u32, u32)
+		(visit_u64, u64)
+		(visit_u128, u128)
+		(visit_f32, f32)
+		(visit_f64, f64)
+		(visit_char, char)
+		(visit_str, &str)
+		(visit_borrowed_str, &'de str)
+		(visit_string, String)
+		(visit_bytes, &[u8])
+		(visit_borrowed_bytes, &'de [u8])
+		(visit_byte_buf, Vec<u8>)
+	);
+
 	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
 		// TODO: improve error message
 		self.inner.expecting(formatter)
 	}
 
-	// fn visit_newtype_struct<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-	// 	self.inner.visit_newtype_struct(Wrap::new(deserializer, self.ignored_fields))
-	// }
+	fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		self.inner.visit_none()
+	}
+
+	fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.inner.visit_some(Wrap::new(deserializer, self.ignored_fields))
+	}
+
+	fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		self.inner.visit_unit()
+	}
+
+	fn visit_newtype_struct<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.inner
+			.visit_newtype_struct(Wrap::new(deserializer, self.ignored_fields))
+	}
+
+	fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+		self.inner.visit_seq(SeqAccess::new(seq, self.ignored_fields))
+	}
+
+	fn visit_enum<A: serde::de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+		self.inner.visit_enum(EnumAccess::new(data, self.ignored_fields))
+	}
 
 	fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+		if self.any && !self.ignored_fields.allow_any_map() {
+			return Err(serde::de::Error::custom(
+				"can not capture the ignored fields of a map deserialized with `deserialize_any`, \
+				 like an untagged or internally tagged enum: use `PreserveIgnoredFieldsBuffered` instead",
+			));
+		}
 		let mut error = None;
 		let value = self
 			.inner
-			.visit_map(MapAccess::new(map, self.ignored_fields, &mut error))?;
+			.visit_map(MapAccess::new(map, self.ignored_fields, &mut error, self.is_struct))?;
 		if let Some(error) = error {
 			Err(error)
 		} else {
@@ -152,11 +384,127 @@ where
 	}
 }
 
+/// Wrapper for a [`serde::de::SeqAccess`] to preserve ignored fields of nested maps.
+struct SeqAccess<'a, S, IgnoredFields> {
+	/// The wrapped [`serde::de::SeqAccess`].
+	inner: S,
+
+	/// The collection to add ignored fields to.
+	ignored_fields: &'a mut IgnoredFields,
+
+	/// The index of the next element.
+	index: usize,
+}
+
+impl<'a, S, IgnoredFields> SeqAccess<'a, S, IgnoredFields> {
+	/// Wrap an existing [`serde::de::SeqAccess`].
+	fn new(inner: S, ignored_fields: &'a mut IgnoredFields) -> Self {
+		Self {
+			inner,
+			ignored_fields,
+			index: 0,
+		}
+	}
+}
+
+impl<'a, 'de, S, IgnoredFields> serde::de::SeqAccess<'de> for SeqAccess<'a, S, IgnoredFields>
+where
+	S: serde::de::SeqAccess<'de>,
+	IgnoredFields: CaptureFields<'de>,
+{
+	type Error = S::Error;
+
+	fn size_hint(&self) -> Option<usize> {
+		self.inner.size_hint()
+	}
+
+	fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		let index = self.index;
+		self.index += 1;
+		if self.ignored_fields.enter(|| Some(PathSegment::Index(index))) {
+			let result = self.inner.next_element_seed(Wrap::new(seed, &mut *self.ignored_fields));
+			self.ignored_fields.leave();
+			result
+		} else {
+			self.inner.next_element_seed(seed)
+		}
+	}
+}
+
+/// Wrapper for a [`serde::de::EnumAccess`] or [`serde::de::VariantAccess`] to preserve ignored fields of the variant data.
+///
+/// The data of a variant is treated as if it was the enum itself,
+/// so the ignored fields of a struct variant end up at the same level as the enum.
+struct EnumAccess<'a, Inner, IgnoredFields> {
+	/// The wrapped [`serde::de::EnumAccess`] or [`serde::de::VariantAccess`].
+	inner: Inner,
+
+	/// The collection to add ignored fields to.
+	ignored_fields: &'a mut IgnoredFields,
+}
+
+impl<'a, Inner, IgnoredFields> EnumAccess<'a, Inner, IgnoredFields> {
+	/// Wrap an existing [`serde::de::EnumAccess`] or [`serde::de::VariantAccess`].
+	fn new(inner: Inner, ignored_fields: &'a mut IgnoredFields) -> Self {
+		Self { inner, ignored_fields }
+	}
+}
+
+impl<'a, 'de, A, IgnoredFields> serde::de::EnumAccess<'de> for EnumAccess<'a, A, IgnoredFields>
+where
+	A: serde::de::EnumAccess<'de>,
+	IgnoredFields: CaptureFields<'de>,
+{
+	type Error = A::Error;
+	type Variant = EnumAccess<'a, A::Variant, IgnoredFields>;
+
+	fn variant_seed<V: serde::de::DeserializeSeed<'de>>(
+		self,
+		seed: V,
+	) -> Result<(V::Value, Self::Variant), Self::Error> {
+		let (value, variant) = self.inner.variant_seed(seed)?;
+		Ok((value, EnumAccess::new(variant, self.ignored_fields)))
+	}
+}
+
+impl<'a, 'de, A, IgnoredFields> serde::de::VariantAccess<'de> for EnumAccess<'a, A, IgnoredFields>
+where
+	A: serde::de::VariantAccess<'de>,
+	IgnoredFields: CaptureFields<'de>,
+{
+	type Error = A::Error;
+
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		self.inner.unit_variant()
+	}
+
+	fn newtype_variant_seed<T: serde::de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		self.inner.newtype_variant_seed(Wrap::new(seed, self.ignored_fields))
+	}
+
+	fn tuple_variant<V: serde::de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.inner.tuple_variant(len, Wrap::new(visitor, self.ignored_fields))
+	}
+
+	fn struct_variant<V: serde::de::Visitor<'de>>(
+		self,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.ignored_fields.set_known_fields(fields);
+		self.inner
+			.struct_variant(fields, Wrap::for_struct(visitor, self.ignored_fields))
+	}
+}
+
 /// Wrapper for a [`serde::de::MapAccess`] to preserve ignored fields.
 struct MapAccess<'a, 'de, M, IgnoredFields>
 where
 	M: serde::de::MapAccess<'de>,
-	IgnoredFields: crate::DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	/// The parent [`serde::de::MapAccess`] being wrapped.
 	parent: Option<M>,
@@ -170,23 +518,31 @@ where
 	/// The previous key we encountered.
 	last_key: Option<Key<'de>>,
 
+	/// The key of the last field that was not ignored.
+	known_key: Option<Key<'de>>,
+
 	/// True if `next_key_seed()` was called, but not yet `next_value_seed()`.
 	retrieved_key: bool,
+
+	/// If true, the map is a struct, so integer keys must not be read as the index of a field.
+	is_struct: bool,
 }
 
 impl<'a, 'de, M, IgnoredFields> MapAccess<'a, 'de, M, IgnoredFields>
 where
 	M: serde::de::MapAccess<'de>,
-	IgnoredFields: crate::DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	/// Wrap an existing [`serde::de::MapAccess`].
-	fn new(parent: M, ignored_fields: &'a mut IgnoredFields, error: &'a mut Option<M::Error>) -> Self {
+	fn new(parent: M, ignored_fields: &'a mut IgnoredFields, error: &'a mut Option<M::Error>, is_struct: bool) -> Self {
 		Self {
 			parent: Some(parent),
 			ignored_fields,
 			error,
 			last_key: None,
+			known_key: None,
 			retrieved_key: false,
+			is_struct,
 		}
 	}
 }
@@ -194,7 +550,7 @@ where
 impl<'a, 'de: 'a, M, U> serde::de::MapAccess<'de> for MapAccess<'a, 'de, M, U>
 where
 	M: serde::de::MapAccess<'de> + 'a,
-	U: DeserializeIgnoredFields<'de>,
+	U: CaptureFields<'de>,
 {
 	type Error = M::Error;
 
@@ -214,9 +570,16 @@ where
 				.last_key
 				.take()
 				.ok_or_else(|| Self::Error::custom("unsupported key type for ignored field"))?;
-			let key = serde::Deserialize::deserialize(key.into_deserializer::<Self::Error>())?;
-			let value = self.next_value()?;
-			self.ignored_fields.insert(key, value)?;
+			let key = self
+				.ignored_fields
+				.deserialize_key(key.into_deserializer::<Self::Error>())?;
+			self.retrieved_key = false;
+			if let Some(parent) = self.parent.as_mut() {
+				let value = parent.next_value_seed(IgnoredValue::new(self.ignored_fields))?;
+				self.ignored_fields
+					.set_position(position_after(self.known_key.as_ref()));
+				self.ignored_fields.insert(key, value)?;
+			}
 		}
 
 		let parent = match self.parent.as_mut() {
@@ -224,7 +587,11 @@ where
 			None => return Ok(None),
 		};
 
-		match parent.next_key_seed(CaptureKey::new(seed, &mut self.last_key))? {
+		let seed = CaptureKey {
+			struct_key: self.is_struct,
+			..CaptureKey::new(seed, &mut self.last_key)
+		};
+		match parent.next_key_seed(seed)? {
 			Some(x) => {
 				self.retrieved_key = true;
 				Ok(Some(x))
@@ -243,7 +610,18 @@ where
 			.parent
 			.as_mut()
 			.expect("called `next_key_seed` without matching call to `next_key_seed`");
-		let result = parent.next_value_seed(CaptureIgnored::new(seed, self.last_key.take(), self.ignored_fields));
+		let position = position_after(self.known_key.as_ref());
+		let result = parent.next_value_seed(CaptureIgnored::new(
+			seed,
+			&mut self.last_key,
+			position,
+			self.ignored_fields,
+		));
+
+		// The key is only taken if the field was ignored.
+		if let Some(key) = self.last_key.take() {
+			self.known_key = Some(key);
+		}
 		result
 	}
 }
@@ -251,12 +629,12 @@ where
 impl<'a, 'de, M, IgnoredFields> Drop for MapAccess<'a, 'de, M, IgnoredFields>
 where
 	M: serde::de::MapAccess<'de>,
-	IgnoredFields: crate::DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	fn drop(&mut self) {
 		if let Some(mut parent) = self.parent.take() {
 			loop {
-				let (key, value) = match parent.next_entry() {
+				let key = match parent.next_key_seed(IgnoredKey::new(self.ignored_fields)) {
 					Ok(None) => break,
 					Ok(Some(x)) => x,
 					Err(e) => {
@@ -264,6 +642,15 @@ where
 						return;
 					},
 				};
+				let value = match parent.next_value_seed(IgnoredValue::new(self.ignored_fields)) {
+					Ok(x) => x,
+					Err(e) => {
+						*self.error = Some(e);
+						return;
+					},
+				};
+				self.ignored_fields
+					.set_position(position_after(self.known_key.as_ref()));
 				if let Err(e) = self.ignored_fields.insert::<M::Error>(key, value) {
 					*self.error = Some(e);
 					return;
@@ -273,6 +660,56 @@ where
 	}
 }
 
+/// Get the position of an ignored field that follows the field with the given key.
+fn position_after<'a>(known_key: Option<&'a Key>) -> FieldPosition<'a> {
+	match known_key {
+		None => FieldPosition::Start,
+		Some(key) => key.as_str().map_or(FieldPosition::End, FieldPosition::After),
+	}
+}
+
+/// [`serde::de::DeserializeSeed`] for the key of an ignored field.
+struct IgnoredKey<'a, IgnoredFields> {
+	/// The collection of ignored fields that deserializes the key.
+	ignored_fields: &'a mut IgnoredFields,
+}
+
+impl<'a, IgnoredFields> IgnoredKey<'a, IgnoredFields> {
+	/// Create a new seed for the key of an ignored field.
+	fn new(ignored_fields: &'a mut IgnoredFields) -> Self {
+		Self { ignored_fields }
+	}
+}
+
+impl<'a, 'de, IgnoredFields: CaptureFields<'de>> serde::de::DeserializeSeed<'de> for IgnoredKey<'a, IgnoredFields> {
+	type Value = IgnoredFields::Key;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.ignored_fields.deserialize_key(deserializer)
+	}
+}
+
+/// [`serde::de::DeserializeSeed`] for the value of an ignored field.
+struct IgnoredValue<'a, IgnoredFields> {
+	/// The collection of ignored fields that deserializes the value.
+	ignored_fields: &'a mut IgnoredFields,
+}
+
+impl<'a, IgnoredFields> IgnoredValue<'a, IgnoredFields> {
+	/// Create a new seed for the value of an ignored field.
+	fn new(ignored_fields: &'a mut IgnoredFields) -> Self {
+		Self { ignored_fields }
+	}
+}
+
+impl<'a, 'de, IgnoredFields: CaptureFields<'de>> serde::de::DeserializeSeed<'de> for IgnoredValue<'a, IgnoredFields> {
+	type Value = IgnoredFields::Value;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.ignored_fields.deserialize_value(deserializer)
+	}
+}
+
 /// Wrapper for a [`serde::de::DeserializeSeed`], [`serde::Deserializer`] and [`serde::de::Visitor`] to store the deserialized value in the `key` field.
 ///
 /// Used to capture a key of a map entry that is being deserialized.
@@ -282,15 +719,28 @@ struct CaptureKey<'a, 'de, Inner> {
 
 	/// The place to store the deserialized value.
 	key: &'a mut Option<Key<'de>>,
+
+	/// If true, the key is deserialized as a field identifier of a struct.
+	///
+	/// The visitor of a field identifier reads an integer as the index of a field.
+	/// Integer keys are passed to it as [`NO_FIELD_INDEX`] instead, so that they are captured as ignored fields.
+	struct_key: bool,
 }
 
 impl<'a, 'de, Inner> CaptureKey<'a, 'de, Inner> {
 	/// Wrap a [`serde::de::DeserializeSeed`], [`serde::Deserializer`] or [`serde::de::Visitor`].
 	fn new(inner: Inner, key: &'a mut Option<Key<'de>>) -> Self {
-		Self { inner, key }
+		Self {
+			inner,
+			key,
+			struct_key: false,
+		}
 	}
 }
 
+/// Field index passed to the visitor of a field identifier for integer keys, which matches no field.
+const NO_FIELD_INDEX: u64 = u64::MAX;
+
 impl<'a, 'de, Seed> serde::de::DeserializeSeed<'de> for CaptureKey<'a, 'de, Seed>
 where
 	Seed: serde::de::DeserializeSeed<'de>,
@@ -298,7 +748,10 @@ where
 	type Value = Seed::Value;
 
 	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-		self.inner.deserialize(CaptureKey::new(deserializer, self.key))
+		self.inner.deserialize(CaptureKey {
+			struct_key: self.struct_key,
+			..CaptureKey::new(deserializer, self.key)
+		})
 	}
 }
 
@@ -341,11 +794,24 @@ where
 			map,
 			struct,
 			enum,
-			identifier,
 			ignored_any,
 		]
 	);
 
+	fn deserialize_identifier<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let visitor = CaptureKey {
+			struct_key: self.struct_key,
+			..CaptureKey::new(visitor, self.key)
+		};
+		if self.inner.is_human_readable() {
+			self.inner.deserialize_identifier(visitor)
+		} else {
+			// Binary formats like CBOR can have keys of any type, but may only accept strings and bytes as identifier.
+			// Let the format report the actual key, so that keys like integers can still be captured as ignored field.
+			self.inner.deserialize_any(visitor)
+		}
+	}
+
 	fn is_human_readable(&self) -> bool {
 		self.inner.is_human_readable()
 	}
@@ -363,24 +829,38 @@ macro_rules! forward_visitor {
 	};
 }
 
+/// Macro to forward integer visitor functions like [`forward_visitor!`], except for the field identifier of a struct.
+///
+/// For the field identifier of a struct, [`NO_FIELD_INDEX`] is passed to `self.inner` instead of the value.
+macro_rules! forward_integer_visitor {
+	($(($ident:ident, $type:ty, $variant:ident))*) => {
+		$(
+			fn $ident<E: serde::de::Error>(self, value: $type) -> Result<Self::Value, E> {
+				*self.key = Some(Key::$variant(value));
+				if self.struct_key {
+					self.inner.visit_u64(NO_FIELD_INDEX)
+				} else {
+					self.inner.$ident(value)
+				}
+			}
+		)*
+	};
+}
+
 impl<'a, 'de, V> serde::de::Visitor<'de> for CaptureKey<'a, 'de, V>
 where
 	V: serde::de::Visitor<'de>,
 {
 	type Value = V::Value;
 
+	forward_integer_visitor!((visit_i8, i8, I8)(visit_i16, i16, I16)(visit_i32, i32, I32)(
+		visit_i64, i64, I64
+	)(visit_i128, i128, I128)(visit_u8, u8, U8)(visit_u16, u16, U16)(
+		visit_u32, u32, U32
+	)(visit_u64, u64, U64)(visit_u128, u128, U128));
+
 	forward_visitor!(
 		(visit_bool, bool, Bool)
-		(visit_i8, i8, I8)
-		(visit_i16, i16, I16)
-		(visit_i32, i32, I32)
-		(visit_i64, i64, I64)
-		(visit_i128, i128, I128)
-		(visit_u8, u8, U8)
-		(visit_u16, u16, U16)
-		(visit_u32, u32, U32)
-		(visit_u64, u64, U64)
-		(visit_u128, u128, U128)
 		(visit_f32, f32, F32)
 		(visit_f64, f64, F64)
 		(visit_char, char, Char)
@@ -447,12 +927,19 @@ where
 }
 
 /// Wrapper for a [`serde::de::DeserializeSeed`], [`serde::Deserializer`] and [`serde::de::Visitor`] to add `ignored_any` map entries to `self.ignored_fields`.
+///
+/// If the ignored fields of nested values are captured, the visitor of other values is wrapped in a [`Wrap`].
 struct CaptureIgnored<'a, 'de, Inner, IgnoredFields> {
 	/// The wrapped object.
 	inner: Inner,
 
 	/// The last deserialized key.
-	key: Option<Key<'de>>,
+	///
+	/// The key is taken out if the value is ignored.
+	key: &'a mut Option<Key<'de>>,
+
+	/// The position of the value relative to the fields that were not ignored.
+	position: FieldPosition<'a>,
 
 	/// The collection of ignored fields.
 	ignored_fields: &'a mut IgnoredFields,
@@ -460,10 +947,16 @@ struct CaptureIgnored<'a, 'de, Inner, IgnoredFields> {
 
 impl<'a, 'de, Inner, IgnoredFields> CaptureIgnored<'a, 'de, Inner, IgnoredFields> {
 	/// Wrap an object.
-	fn new(inner: Inner, key: Option<Key<'de>>, ignored_fields: &'a mut IgnoredFields) -> Self {
+	fn new(
+		inner: Inner,
+		key: &'a mut Option<Key<'de>>,
+		position: FieldPosition<'a>,
+		ignored_fields: &'a mut IgnoredFields,
+	) -> Self {
 		Self {
 			inner,
 			key,
+			position,
 			ignored_fields,
 		}
 	}
@@ -472,26 +965,30 @@ impl<'a, 'de, Inner, IgnoredFields> CaptureIgnored<'a, 'de, Inner, IgnoredFields
 impl<'a, 'de, Seed, IgnoredFields> serde::de::DeserializeSeed<'de> for CaptureIgnored<'a, 'de, Seed, IgnoredFields>
 where
 	Seed: serde::de::DeserializeSeed<'de>,
-	IgnoredFields: DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	type Value = Seed::Value;
 
 	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
-		self.inner
-			.deserialize(CaptureIgnored::new(deserializer, self.key, self.ignored_fields))
+		self.inner.deserialize(CaptureIgnored::new(
+			deserializer,
+			self.key,
+			self.position,
+			self.ignored_fields,
+		))
 	}
 }
 
 impl<'a, 'de, D, IgnoredFields> serde::Deserializer<'de> for CaptureIgnored<'a, 'de, D, IgnoredFields>
 where
 	D: serde::Deserializer<'de>,
-	IgnoredFields: DeserializeIgnoredFields<'de>,
+	IgnoredFields: CaptureFields<'de>,
 {
 	type Error = D::Error;
 
 	forward_deserializer!(
-		fn deserialize(self, visitor) { } for [
-			// any,
+		fn deserialize(self, visitor) nested for [
+			any,
 			bool,
 			i8,
 			i16,
@@ -518,26 +1015,63 @@ where
 			tuple,
 			tuple_struct,
 			map,
-			struct,
-			enum,
 			identifier,
 		]
 	);
 
-	fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		eprintln!("CaptureIgnored::deserialize_any");
-		self.inner.deserialize_any(visitor)
+	fn deserialize_struct<V: serde::de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let ignored_fields = self.ignored_fields;
+		let key = self.key;
+		if ignored_fields.enter(|| key.as_ref().and_then(Key::to_path_segment)) {
+			ignored_fields.set_name(name);
+			ignored_fields.set_known_fields(fields);
+			let result = self
+				.inner
+				.deserialize_struct(name, fields, Wrap::for_struct(visitor, &mut *ignored_fields));
+			ignored_fields.leave();
+			result
+		} else {
+			self.inner.deserialize_struct(name, fields, visitor)
+		}
+	}
+
+	fn deserialize_enum<V: serde::de::Visitor<'de>>(
+		self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let ignored_fields = self.ignored_fields;
+		let key = self.key;
+		if ignored_fields.enter(|| key.as_ref().and_then(Key::to_path_segment)) {
+			ignored_fields.set_name(name);
+			ignored_fields.set_known_variants(variants);
+			let result = self
+				.inner
+				.deserialize_enum(name, variants, Wrap::new(visitor, &mut *ignored_fields));
+			ignored_fields.leave();
+			result
+		} else {
+			self.inner.deserialize_enum(name, variants, visitor)
+		}
 	}
 
 	fn deserialize_ignored_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-		use serde::de::{Deserialize, Error};
+		use serde::de::Error;
 
 		let key = self
 			.key
+			.take()
 			.ok_or_else(|| Self::Error::custom("unsupported key type for ignored field"))?
 			.into_deserializer();
-		let key = IgnoredFields::Key::deserialize(key)?;
-		let value = IgnoredFields::Value::deserialize(self.inner)?;
+		let key = self.ignored_fields.deserialize_key(key)?;
+		let value = self.ignored_fields.deserialize_value(self.inner)?;
+		self.ignored_fields.set_position(self.position);
 		self.ignored_fields.insert(key, value)?;
 		visitor.visit_unit()
 	}
@@ -0,0 +1,223 @@
+use crate::forward::{forward_deserialize_ignored_fields, forward_serialize_ignored_fields};
+use crate::{DeserializeIgnoredFields, SerializeIgnoredFields};
+
+/// Wrapper for ignored fields that keeps the first value of duplicate keys.
+///
+/// Later values for the same key are silently dropped.
+/// It can be used with any type that implements [`DeserializeIgnoredFields`].
+/// Duplicate keys are found with [`DeserializeIgnoredFields::get_mut()`],
+/// so collections that do not support it, like [`IgnoredFieldNames`](crate::IgnoredFieldNames), produce an error.
+///
+/// Without a wrapper, most types report an error for duplicate keys.
+/// See also [`LastWins`] and [`CollectAll`].
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{FirstWins, PreserveIgnoredFields};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+/// }
+///
+/// let data = r#"{"name": "Zohan", "color": "red", "color": "blue"}"#;
+/// let_assert!(Ok(thing) = serde_json::from_str::<PreserveIgnoredFields<Thing, FirstWins<BTreeMap<String, String>>>>(data));
+/// assert!(thing.ignored_fields.0["color"] == "red");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FirstWins<U>(pub U);
+
+/// Wrapper for ignored fields that keeps the last value of duplicate keys.
+///
+/// Later values for the same key replace the earlier value in place,
+/// so the field keeps the position of the first occurrence.
+/// It can be used with any type that implements [`DeserializeIgnoredFields`].
+/// Duplicate keys are found with [`DeserializeIgnoredFields::get_mut()`],
+/// so collections that do not support it, like [`IgnoredFieldNames`](crate::IgnoredFieldNames), produce an error.
+///
+/// Without a wrapper, most types report an error for duplicate keys.
+/// See also [`FirstWins`] and [`CollectAll`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LastWins<U>(pub U);
+
+/// Wrapper for ignored fields that collects all values of duplicate keys.
+///
+/// The wrapped type must store a `Vec` of values for each key, like `BTreeMap<K, Vec<V>>`.
+/// Later values are appended to the `Vec` of the first occurrence,
+/// which is found with [`DeserializeIgnoredFields::get_mut()`] like for [`FirstWins`].
+/// When serialized, each value is written as a separate field, so duplicate keys are preserved.
+///
+/// Each value is deserialized through the wrapped type as a `Vec` with one element,
+/// so wrappers inside of it, like [`Limited`](crate::Limited), count each value as a sequence.
+///
+/// Note that `Vec<(K, V)>` also keeps all duplicate keys, without the need for a wrapper.
+/// See also [`FirstWins`] and [`LastWins`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollectAll<U>(pub U);
+
+impl<'de, U: DeserializeIgnoredFields<'de>> DeserializeIgnoredFields<'de> for FirstWins<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_deserialize_ignored_fields!(0, except insert);
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		check_get_mut(&self.0, "FirstWins")?;
+		if self.0.get_mut(&key).is_some() {
+			return Ok(());
+		}
+		self.0.insert(key, value)
+	}
+}
+
+impl<U: SerializeIgnoredFields> SerializeIgnoredFields for FirstWins<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_serialize_ignored_fields!(0);
+}
+
+impl<'de, U: DeserializeIgnoredFields<'de>> DeserializeIgnoredFields<'de> for LastWins<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_deserialize_ignored_fields!(0, except insert);
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		check_get_mut(&self.0, "LastWins")?;
+		match self.0.get_mut(&key) {
+			Some(existing) => {
+				*existing = value;
+				Ok(())
+			},
+			None => self.0.insert(key, value),
+		}
+	}
+}
+
+impl<U: SerializeIgnoredFields> SerializeIgnoredFields for LastWins<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_serialize_ignored_fields!(0);
+}
+
+impl<'de, U, V> DeserializeIgnoredFields<'de> for CollectAll<U>
+where
+	U: DeserializeIgnoredFields<'de, Value = Vec<V>>,
+	V: serde::Deserialize<'de>,
+{
+	type Key = U::Key;
+	type Value = V;
+
+	forward_deserialize_ignored_fields!(0, except insert, get_mut, deserialize_value);
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		check_get_mut(&self.0, "CollectAll")?;
+		match self.0.get_mut(&key) {
+			Some(values) => {
+				values.push(value);
+				Ok(())
+			},
+			None => self.0.insert(key, vec![value]),
+		}
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		self.0.get_mut(key)?.first_mut()
+	}
+
+	fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error> {
+		// Let the wrapped type deserialize the value as `Vec` with one element, so it can inspect the value.
+		let mut values = self.0.deserialize_value(SingleElement(deserializer))?;
+		match (values.pop(), values.is_empty()) {
+			(Some(value), true) => Ok(value),
+			_ => Err(serde::de::Error::custom(
+				"expected the wrapped ignored fields to deserialize exactly one value",
+			)),
+		}
+	}
+}
+
+impl<U> SerializeIgnoredFields for CollectAll<U>
+where
+	U: SerializeIgnoredFields,
+	U::Value: IntoIterator,
+	for<'a> &'a U::Value: IntoIterator<Item = &'a <U::Value as IntoIterator>::Item>,
+	<U::Value as IntoIterator>::Item: serde::Serialize,
+{
+	type Key = U::Key;
+	type Value = <U::Value as IntoIterator>::Item;
+
+	// Keep the default `is_empty()`, since a key can have an empty list of values.
+	forward_serialize_ignored_fields!(0, except len, is_empty, iter);
+
+	fn len(&self) -> usize {
+		self.0.iter().map(|(_, values)| values.into_iter().count()).sum()
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		self.0
+			.iter()
+			.flat_map(|(key, values)| values.into_iter().map(move |value| (key, value)))
+	}
+}
+
+/// Check that the wrapped ignored fields can find duplicate keys with [`DeserializeIgnoredFields::get_mut()`].
+///
+/// Without it, a duplicate policy would silently keep all duplicates.
+fn check_get_mut<'de, U: DeserializeIgnoredFields<'de>, E: serde::de::Error>(
+	fields: &U,
+	wrapper: &str,
+) -> Result<(), E> {
+	if fields.supports_get_mut() {
+		Ok(())
+	} else {
+		Err(E::custom(format_args!(
+			"`{wrapper}` can not detect duplicate ignored fields: the wrapped type does not support `get_mut()`"
+		)))
+	}
+}
+
+/// Deserializer that presents a value as a sequence with one element.
+struct SingleElement<D>(D);
+
+impl<'de, D: serde::Deserializer<'de>> serde::Deserializer<'de> for SingleElement<D> {
+	type Error = D::Error;
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf option unit unit_struct newtype_struct seq tuple
+		tuple_struct map struct enum identifier ignored_any
+	}
+
+	fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_seq(SingleElementAccess(Some(self.0)))
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.0.is_human_readable()
+	}
+}
+
+/// Sequence access for [`SingleElement`].
+struct SingleElementAccess<D>(Option<D>);
+
+impl<'de, D: serde::Deserializer<'de>> serde::de::SeqAccess<'de> for SingleElementAccess<D> {
+	type Error = D::Error;
+
+	fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		match self.0.take() {
+			Some(deserializer) => seed.deserialize(deserializer).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(usize::from(self.0.is_some()))
+	}
+}
@@ -0,0 +1,33 @@
+impl<'de> crate::DeserializeIgnoredFields<'de> for bson::Document {
+	type Key = String;
+	type Value = bson::Bson;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		if self.contains_key(&key) {
+			return Err(E::custom(format!("duplicate field: {key:?}")));
+		}
+		bson::Document::insert(self, key, value);
+		Ok(())
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		bson::Document::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl crate::SerializeIgnoredFields for bson::Document {
+	type Key = String;
+	type Value = bson::Bson;
+
+	fn len(&self) -> usize {
+		bson::Document::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		bson::Document::iter(self)
+	}
+}
@@ -0,0 +1,51 @@
+/// Serializes the entries of a [`ciborium::Value::Map`].
+///
+/// [`ciborium::Value`] has no default value, so it can not collect ignored fields.
+/// Use `Vec<(ciborium::Value, ciborium::Value)>` to deserialize them instead,
+/// which is the same type that [`ciborium::Value::Map`] holds.
+///
+/// [`ciborium::Value::Null`] has no ignored fields.
+/// Serializing any other value is an error.
+impl crate::SerializeIgnoredFields for ciborium::Value {
+	type Key = ciborium::Value;
+	type Value = ciborium::Value;
+
+	fn len(&self) -> usize {
+		match self {
+			ciborium::Value::Null => 0,
+			ciborium::Value::Map(map) => map.len(),
+			// Count other values as a field, so they are not silently skipped but reported by `check()`.
+			_ => 1,
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		self.as_map().into_iter().flatten().map(|(key, value)| (key, value))
+	}
+
+	fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+		match self {
+			ciborium::Value::Null | ciborium::Value::Map(_) => Ok(()),
+			other => Err(E::custom(format!(
+				"invalid type for ignored fields: expected a CBOR map, found {}",
+				kind(other)
+			))),
+		}
+	}
+}
+
+/// Describe the kind of a CBOR value for error messages.
+fn kind(value: &ciborium::Value) -> &'static str {
+	match value {
+		ciborium::Value::Integer(_) => "an integer",
+		ciborium::Value::Bytes(_) => "a byte string",
+		ciborium::Value::Float(_) => "a float",
+		ciborium::Value::Text(_) => "a text string",
+		ciborium::Value::Bool(_) => "a boolean",
+		ciborium::Value::Null => "null",
+		ciborium::Value::Tag(..) => "a tag",
+		ciborium::Value::Array(_) => "an array",
+		ciborium::Value::Map(_) => "a map",
+		_ => "an unknown value",
+	}
+}
@@ -0,0 +1,45 @@
+impl<'de, K, V, S> crate::DeserializeIgnoredFields<'de> for indexmap::IndexMap<K, V, S>
+where
+	K: serde::Deserialize<'de> + Eq + std::hash::Hash + std::fmt::Debug,
+	V: serde::Deserialize<'de> + std::fmt::Debug,
+	S: std::hash::BuildHasher + Default,
+{
+	type Key = K;
+	type Value = V;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		use indexmap::map::Entry;
+		match self.entry(key) {
+			Entry::Vacant(x) => {
+				x.insert(value);
+				Ok(())
+			},
+			Entry::Occupied(x) => Err(E::custom(format!("duplicate field: {:?}", x.key()))),
+		}
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		indexmap::IndexMap::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl<K, V, S> crate::SerializeIgnoredFields for indexmap::IndexMap<K, V, S>
+where
+	K: serde::Serialize,
+	V: serde::Serialize,
+{
+	type Key = K;
+	type Value = V;
+
+	fn len(&self) -> usize {
+		indexmap::IndexMap::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		indexmap::IndexMap::iter(self)
+	}
+}
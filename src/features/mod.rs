@@ -1,3 +1,23 @@
+#[cfg(feature = "bson")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "bson")))]
+mod bson;
+
+#[cfg(feature = "ciborium")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "ciborium")))]
+mod ciborium;
+
+#[cfg(feature = "indexmap")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "indexmap")))]
+mod indexmap;
+
+#[cfg(feature = "rmpv")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "rmpv")))]
+mod rmpv;
+
+#[cfg(feature = "ron")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "ron")))]
+pub(crate) mod ron;
+
 #[cfg(feature = "serde_json")]
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "serde_json")))]
 mod serde_json;
@@ -17,3 +37,16 @@ mod toml;
 #[cfg(feature = "schemars")]
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "schemars")))]
 mod schemars;
+
+#[cfg(feature = "schemars1")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "schemars1")))]
+mod schemars1;
+
+#[cfg(feature = "ron")]
+pub use ron::AsRonStruct;
+
+#[cfg(feature = "schemars")]
+pub use schemars::JsonSchemaIgnoredFields;
+
+#[cfg(feature = "schemars1")]
+pub use schemars1::JsonSchema1IgnoredFields;
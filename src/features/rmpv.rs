@@ -0,0 +1,50 @@
+/// Serializes the entries of a [`rmpv::Value::Map`].
+///
+/// [`rmpv::Value`] has no default value, so it can not collect ignored fields.
+/// Use `Vec<(rmpv::Value, rmpv::Value)>` to deserialize them instead,
+/// which is the same type that [`rmpv::Value::Map`] holds.
+///
+/// [`rmpv::Value::Nil`] has no ignored fields.
+/// Serializing any other value is an error.
+impl crate::SerializeIgnoredFields for rmpv::Value {
+	type Key = rmpv::Value;
+	type Value = rmpv::Value;
+
+	fn len(&self) -> usize {
+		match self {
+			rmpv::Value::Nil => 0,
+			rmpv::Value::Map(map) => map.len(),
+			// Count other values as a field, so they are not silently skipped but reported by `check()`.
+			_ => 1,
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		self.as_map().into_iter().flatten().map(|(key, value)| (key, value))
+	}
+
+	fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+		match self {
+			rmpv::Value::Nil | rmpv::Value::Map(_) => Ok(()),
+			other => Err(E::custom(format!(
+				"invalid type for ignored fields: expected a MessagePack map, found {}",
+				kind(other)
+			))),
+		}
+	}
+}
+
+/// Describe the kind of a MessagePack value for error messages.
+fn kind(value: &rmpv::Value) -> &'static str {
+	match value {
+		rmpv::Value::Nil => "nil",
+		rmpv::Value::Boolean(_) => "a boolean",
+		rmpv::Value::Integer(_) => "an integer",
+		rmpv::Value::F32(_) | rmpv::Value::F64(_) => "a float",
+		rmpv::Value::String(_) => "a string",
+		rmpv::Value::Binary(_) => "binary data",
+		rmpv::Value::Array(_) => "an array",
+		rmpv::Value::Map(_) => "a map",
+		rmpv::Value::Ext(..) => "an extension value",
+	}
+}
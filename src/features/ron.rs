@@ -0,0 +1,237 @@
+use crate::forward::{forward_deserialize_ignored_fields, forward_serialize_ignored_fields};
+
+impl<'de> crate::DeserializeIgnoredFields<'de> for ron::Map {
+	type Key = ron::Value;
+	type Value = ron::Value;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		if self.get(&key).is_some() {
+			return Err(E::custom(format!("duplicate field: {key:?}")));
+		}
+		ron::Map::insert(self, key, value);
+		Ok(())
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		ron::Map::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl crate::SerializeIgnoredFields for ron::Map {
+	type Key = ron::Value;
+	type Value = ron::Value;
+
+	fn len(&self) -> usize {
+		ron::Map::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		ron::Map::iter(self)
+	}
+}
+
+/// Serializes the entries of a [`ron::Value::Map`].
+///
+/// [`ron::Value`] has no default value, so it can not collect ignored fields.
+/// Use [`ron::Map`] to deserialize them instead.
+///
+/// [`ron::Value::Unit`] has no ignored fields.
+/// Serializing any other value is an error.
+impl crate::SerializeIgnoredFields for ron::Value {
+	type Key = ron::Value;
+	type Value = ron::Value;
+
+	fn len(&self) -> usize {
+		match self {
+			ron::Value::Unit => 0,
+			ron::Value::Map(map) => map.len(),
+			// Count other values as a field, so they are not silently skipped but reported by `check()`.
+			_ => 1,
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		let map = match self {
+			ron::Value::Map(map) => Some(map),
+			_ => None,
+		};
+		map.into_iter().flat_map(|map| map.iter())
+	}
+
+	fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+		match self {
+			ron::Value::Unit | ron::Value::Map(_) => Ok(()),
+			other => Err(E::custom(format!(
+				"invalid type for ignored fields: expected a RON map, found {}",
+				kind(other)
+			))),
+		}
+	}
+}
+
+/// Describe the kind of a RON value for error messages.
+fn kind(value: &ron::Value) -> &'static str {
+	match value {
+		ron::Value::Bool(_) => "a boolean",
+		ron::Value::Char(_) => "a character",
+		ron::Value::Map(_) => "a map",
+		ron::Value::Number(_) => "a number",
+		ron::Value::Option(_) => "an option",
+		ron::Value::String(_) => "a string",
+		ron::Value::Bytes(_) => "a byte string",
+		ron::Value::Seq(_) => "a sequence",
+		ron::Value::Unit => "a unit value",
+	}
+}
+
+/// Wrapper for ignored fields that are written to RON as fields of a struct.
+///
+/// RON distinguishes structs from maps, and can not deserialize a struct from a map.
+/// Normally, a struct or struct variant with ignored fields is serialized as a map, since the fields of a struct can only have static names.
+/// With this wrapper, it is written in RON struct syntax instead, with the ignored fields added as struct fields.
+/// If an ignored field has a key that is not a string, the struct is still serialized as a map.
+///
+/// It can be used with any type that implements [`DeserializeIgnoredFields`](crate::DeserializeIgnoredFields) or [`SerializeIgnoredFields`](crate::SerializeIgnoredFields).
+/// Only serialize it with a RON serializer: the struct is passed to the serializer as a [`ron::value::RawValue`],
+/// which other formats write as a string.
+///
+/// The RON text of the struct is produced separately with [`ron::to_string()`],
+/// so it does not use the [`ron::ser::PrettyConfig`] or the extensions of the serializer.
+/// Everything outside of the struct is still written by the serializer itself.
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{AsRonStruct, PreserveIgnoredFields};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+/// }
+///
+/// let data = r#"(name:"Zohan",colour:"red")"#;
+/// let_assert!(Ok(thing) = ron::from_str::<PreserveIgnoredFields<Thing, AsRonStruct<ron::Map>>>(data));
+/// let_assert!(Ok(serialized) = ron::to_string(&thing));
+/// assert!(serialized == data);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AsRonStruct<U>(pub U);
+
+impl<'de, U: crate::DeserializeIgnoredFields<'de>> crate::DeserializeIgnoredFields<'de> for AsRonStruct<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_deserialize_ignored_fields!(0);
+}
+
+impl<U: crate::SerializeIgnoredFields> crate::SerializeIgnoredFields for AsRonStruct<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_serialize_ignored_fields!(0, except ron_struct);
+
+	fn ron_struct(&self) -> bool {
+		true
+	}
+}
+
+/// Writer for a RON struct or struct variant with ignored fields.
+///
+/// The fields of a struct can only have static names, so ignored fields can not be added through [`serde::ser::SerializeStruct`].
+/// Instead, the struct is written as RON text and passed to the serializer as a [`ron::value::RawValue`].
+/// The text is not pretty printed and does not use RON extensions, see [`AsRonStruct`].
+pub(crate) struct RonStruct<S> {
+	/// The wrapped serializer.
+	serializer: S,
+
+	/// The RON text written so far.
+	text: String,
+
+	/// If true, no field has been written yet.
+	empty: bool,
+}
+
+impl<S: serde::Serializer> RonStruct<S> {
+	/// Start writing a struct, or a struct variant with the given name.
+	pub(crate) fn new(serializer: S, variant: Option<&str>) -> Self {
+		let mut text = String::new();
+		if let Some(variant) = variant {
+			write_identifier(&mut text, variant);
+		}
+		text.push('(');
+		Self {
+			serializer,
+			text,
+			empty: true,
+		}
+	}
+
+	/// Write a field with the given name.
+	fn write_field<V: ?Sized + serde::Serialize>(&mut self, name: &str, value: &V) -> Result<(), S::Error> {
+		let value = ron::to_string(value).map_err(serde::ser::Error::custom)?;
+		if !std::mem::take(&mut self.empty) {
+			self.text.push(',');
+		}
+		write_identifier(&mut self.text, name);
+		self.text.push(':');
+		self.text.push_str(&value);
+		Ok(())
+	}
+}
+
+impl<S: serde::Serializer> serde::ser::SerializeStruct for RonStruct<S> {
+	type Error = S::Error;
+	type Ok = S::Ok;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.write_field(key, value)
+	}
+
+	fn end(mut self) -> Result<Self::Ok, Self::Error> {
+		self.text.push(')');
+		let raw =
+			ron::value::RawValue::from_boxed_ron(self.text.into_boxed_str()).map_err(serde::ser::Error::custom)?;
+		serde::Serialize::serialize(&raw, self.serializer)
+	}
+}
+
+impl<S: serde::Serializer> crate::serialize::SerializeFields for RonStruct<S> {
+	type Error = S::Error;
+
+	fn serialize_ignored<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize,
+	{
+		let Some(name) = crate::serialize::field_name(key) else {
+			return Err(serde::ser::Error::custom(
+				"ignored field without a string key can not be added to a struct",
+			));
+		};
+		self.write_field(&name, value)
+	}
+}
+
+/// Write a RON identifier, as raw identifier if needed.
+///
+/// Names that are not valid raw identifiers either are rejected when the text is parsed as [`ron::value::RawValue`].
+fn write_identifier(text: &mut String, name: &str) {
+	let mut chars = name.chars();
+	let plain = chars.next().is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+		&& chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+		&& ![
+			"true", "false", "Some", "None", "inf", "inff32", "inff64", "NaN", "NaNf32", "NaNf64",
+		]
+		.contains(&name);
+	if !plain {
+		text.push_str("r#");
+	}
+	text.push_str(name);
+}
@@ -1,31 +1,180 @@
-/// Forwards directly to the [`schemars::JsonSchema`] implementation of `T`.
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject};
+use std::collections::{BTreeMap, HashMap};
+
+/// Trait for collections of ignored fields that can describe their values in a JSON schema.
+///
+/// The schema is used as `additionalProperties` of the schema of [`PreserveIgnoredFields`](crate::PreserveIgnoredFields).
+pub trait JsonSchemaIgnoredFields {
+	/// Generate the schema for the values of the ignored fields.
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema;
+}
+
+/// Uses the schema of `T`, with the schema of the ignored field values as `additionalProperties`.
+///
+/// If `T` has an object schema, its `additionalProperties` are replaced,
+/// so ignored fields are allowed even if `T` uses `#[serde(deny_unknown_fields)]`.
+/// Other schemas are used unchanged.
+///
+/// The schema differs from the schema of `T`, so it is always inlined instead of referenced by name.
 impl<T, IgnoredFields> schemars::JsonSchema for crate::PreserveIgnoredFields<T, IgnoredFields>
 where
 	T: schemars::JsonSchema,
+	IgnoredFields: JsonSchemaIgnoredFields,
 {
 	fn schema_name() -> String {
 		T::schema_name()
 	}
 
 	fn is_referenceable() -> bool {
-		T::is_referenceable()
+		false
 	}
 
 	fn schema_id() -> std::borrow::Cow<'static, str> {
-		T::schema_id()
+		format!("serde_ignored_fields::PreserveIgnoredFields<{}>", T::schema_id()).into()
 	}
 
-	fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
-		T::json_schema(gen)
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		let schema = T::json_schema(gen);
+		with_additional_properties(schema, IgnoredFields::value_schema(gen))
 	}
 
-	fn _schemars_private_non_optional_json_schema(
-		gen: &mut schemars::gen::SchemaGenerator,
-	) -> schemars::schema::Schema {
-		T::_schemars_private_non_optional_json_schema(gen)
+	fn _schemars_private_non_optional_json_schema(gen: &mut SchemaGenerator) -> Schema {
+		let schema = T::_schemars_private_non_optional_json_schema(gen);
+		with_additional_properties(schema, IgnoredFields::value_schema(gen))
 	}
 
 	fn _schemars_private_is_option() -> bool {
 		T::_schemars_private_is_option()
 	}
 }
+
+/// Set the `additionalProperties` of an object schema.
+fn with_additional_properties(mut schema: Schema, additional_properties: Schema) -> Schema {
+	if let Schema::Object(SchemaObject {
+		object: Some(object), ..
+	}) = &mut schema
+	{
+		object.additional_properties = Some(Box::new(additional_properties));
+	}
+	schema
+}
+
+/// Implement [`JsonSchemaIgnoredFields`] for a map type that accepts any value.
+macro_rules! impl_any_value {
+	($($(#[$attr:meta])* $type:ty),* $(,)?) => {
+		$(
+			$(#[$attr])*
+			impl JsonSchemaIgnoredFields for $type {
+				fn value_schema(_gen: &mut SchemaGenerator) -> Schema {
+					Schema::Bool(true)
+				}
+			}
+		)*
+	};
+}
+
+impl_any_value!(
+	crate::Map,
+	Vec<(crate::Value, crate::Value)>,
+	#[cfg(feature = "bson")]
+	bson::Document,
+	#[cfg(feature = "ciborium")]
+	Vec<(ciborium::Value, ciborium::Value)>,
+	#[cfg(feature = "rmpv")]
+	Vec<(rmpv::Value, rmpv::Value)>,
+	#[cfg(feature = "ron")]
+	ron::Map,
+	#[cfg(feature = "serde_yaml")]
+	serde_yaml::Mapping,
+	#[cfg(feature = "serde_yml")]
+	serde_yml::Mapping,
+	#[cfg(feature = "toml")]
+	toml::Table,
+);
+
+/// Ignored values are skipped, so any value is accepted.
+impl<K> JsonSchemaIgnoredFields for crate::IgnoredFieldNames<K> {
+	fn value_schema(_gen: &mut SchemaGenerator) -> Schema {
+		Schema::Bool(true)
+	}
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonSchemaIgnoredFields for serde_json::Map<String, serde_json::Value> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<serde_json::Value>()
+	}
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonSchemaIgnoredFields for serde_json::Value {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<serde_json::Value>()
+	}
+}
+
+impl<K, V: schemars::JsonSchema> JsonSchemaIgnoredFields for BTreeMap<K, V> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+impl<K, V: schemars::JsonSchema, S> JsonSchemaIgnoredFields for HashMap<K, V, S> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+/// Only implemented for string keys, since the keys of a JSON object are strings.
+///
+/// The `Vec` types for formats that allow other keys accept any value instead.
+impl<V: schemars::JsonSchema> JsonSchemaIgnoredFields for Vec<(String, V)> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V: schemars::JsonSchema, S> JsonSchemaIgnoredFields for indexmap::IndexMap<K, V, S> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+/// Implement [`JsonSchemaIgnoredFields`] for a wrapper by forwarding to the wrapped type.
+macro_rules! impl_wrapper {
+	($($type:ident),* $(,)?) => {
+		$(
+			impl<U: JsonSchemaIgnoredFields> JsonSchemaIgnoredFields for crate::$type<U> {
+				fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+					U::value_schema(gen)
+				}
+			}
+		)*
+	};
+}
+
+impl_wrapper!(FirstWins, LastWins, ValueWins, IgnoredWins, KeepOrder, WithMetadata);
+
+impl<U, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> JsonSchemaIgnoredFields
+	for crate::Limited<U, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>
+where
+	U: JsonSchemaIgnoredFields,
+{
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		U::value_schema(gen)
+	}
+}
+
+/// Each value is a separate field, so the schema is that of a single value.
+impl<U> JsonSchemaIgnoredFields for crate::CollectAll<U>
+where
+	U: crate::SerializeIgnoredFields,
+	U::Value: IntoIterator,
+	<U::Value as IntoIterator>::Item: schemars::JsonSchema,
+{
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<<U::Value as IntoIterator>::Item>()
+	}
+}
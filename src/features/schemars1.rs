@@ -0,0 +1,187 @@
+use schemars1::{Schema, SchemaGenerator};
+use std::collections::{BTreeMap, HashMap};
+
+/// Trait for collections of ignored fields that can describe their values in a JSON schema of `schemars` 1.x.
+///
+/// The schema is used as `additionalProperties` of the schema of [`PreserveIgnoredFields`](crate::PreserveIgnoredFields).
+/// This is the `schemars` 1.x counterpart of [`JsonSchemaIgnoredFields`](crate::JsonSchemaIgnoredFields).
+pub trait JsonSchema1IgnoredFields {
+	/// Generate the schema for the values of the ignored fields.
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema;
+}
+
+/// Uses the schema of `T`, with the schema of the ignored field values as `additionalProperties`.
+///
+/// If `T` has an object schema, its `additionalProperties` are replaced,
+/// so ignored fields are allowed even if `T` uses `#[serde(deny_unknown_fields)]`.
+/// Other schemas are used unchanged.
+///
+/// The schema differs from the schema of `T`, so it is always inlined instead of referenced by name.
+impl<T, IgnoredFields> schemars1::JsonSchema for crate::PreserveIgnoredFields<T, IgnoredFields>
+where
+	T: schemars1::JsonSchema,
+	IgnoredFields: JsonSchema1IgnoredFields,
+{
+	fn inline_schema() -> bool {
+		true
+	}
+
+	fn schema_name() -> std::borrow::Cow<'static, str> {
+		T::schema_name()
+	}
+
+	fn schema_id() -> std::borrow::Cow<'static, str> {
+		format!("serde_ignored_fields::PreserveIgnoredFields<{}>", T::schema_id()).into()
+	}
+
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		let schema = T::json_schema(gen);
+		with_additional_properties(schema, IgnoredFields::value_schema(gen))
+	}
+
+	fn _schemars_private_non_optional_json_schema(gen: &mut SchemaGenerator) -> Schema {
+		let schema = T::_schemars_private_non_optional_json_schema(gen);
+		with_additional_properties(schema, IgnoredFields::value_schema(gen))
+	}
+
+	fn _schemars_private_is_option() -> bool {
+		T::_schemars_private_is_option()
+	}
+}
+
+/// Set the `additionalProperties` of an object schema.
+fn with_additional_properties(mut schema: Schema, additional_properties: Schema) -> Schema {
+	if is_object_schema(&schema) {
+		schema.insert("additionalProperties".into(), additional_properties.to_value());
+	}
+	schema
+}
+
+/// Check if a schema describes an object.
+fn is_object_schema(schema: &Schema) -> bool {
+	let Some(schema) = schema.as_object() else {
+		return false;
+	};
+	schema.get("type").and_then(|x| x.as_str()) == Some("object")
+		|| schema.contains_key("properties")
+		|| schema.contains_key("additionalProperties")
+}
+
+/// Implement [`JsonSchema1IgnoredFields`] for a map type that accepts any value.
+macro_rules! impl_any_value {
+	($($(#[$attr:meta])* $type:ty),* $(,)?) => {
+		$(
+			$(#[$attr])*
+			impl JsonSchema1IgnoredFields for $type {
+				fn value_schema(_gen: &mut SchemaGenerator) -> Schema {
+					Schema::from(true)
+				}
+			}
+		)*
+	};
+}
+
+impl_any_value!(
+	crate::Map,
+	Vec<(crate::Value, crate::Value)>,
+	#[cfg(feature = "bson")]
+	bson::Document,
+	#[cfg(feature = "ciborium")]
+	Vec<(ciborium::Value, ciborium::Value)>,
+	#[cfg(feature = "rmpv")]
+	Vec<(rmpv::Value, rmpv::Value)>,
+	#[cfg(feature = "ron")]
+	ron::Map,
+	#[cfg(feature = "serde_yaml")]
+	serde_yaml::Mapping,
+	#[cfg(feature = "serde_yml")]
+	serde_yml::Mapping,
+	#[cfg(feature = "toml")]
+	toml::Table,
+);
+
+/// Ignored values are skipped, so any value is accepted.
+impl<K> JsonSchema1IgnoredFields for crate::IgnoredFieldNames<K> {
+	fn value_schema(_gen: &mut SchemaGenerator) -> Schema {
+		Schema::from(true)
+	}
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonSchema1IgnoredFields for serde_json::Map<String, serde_json::Value> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<serde_json::Value>()
+	}
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonSchema1IgnoredFields for serde_json::Value {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<serde_json::Value>()
+	}
+}
+
+impl<K, V: schemars1::JsonSchema> JsonSchema1IgnoredFields for BTreeMap<K, V> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+impl<K, V: schemars1::JsonSchema, S> JsonSchema1IgnoredFields for HashMap<K, V, S> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+/// Only implemented for string keys, since the keys of a JSON object are strings.
+///
+/// The `Vec` types for formats that allow other keys accept any value instead.
+impl<V: schemars1::JsonSchema> JsonSchema1IgnoredFields for Vec<(String, V)> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V: schemars1::JsonSchema, S> JsonSchema1IgnoredFields for indexmap::IndexMap<K, V, S> {
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<V>()
+	}
+}
+
+/// Implement [`JsonSchema1IgnoredFields`] for a wrapper by forwarding to the wrapped type.
+macro_rules! impl_wrapper {
+	($($type:ident),* $(,)?) => {
+		$(
+			impl<U: JsonSchema1IgnoredFields> JsonSchema1IgnoredFields for crate::$type<U> {
+				fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+					U::value_schema(gen)
+				}
+			}
+		)*
+	};
+}
+
+impl_wrapper!(FirstWins, LastWins, ValueWins, IgnoredWins, KeepOrder, WithMetadata);
+
+impl<U, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> JsonSchema1IgnoredFields
+	for crate::Limited<U, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>
+where
+	U: JsonSchema1IgnoredFields,
+{
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		U::value_schema(gen)
+	}
+}
+
+/// Each value is a separate field, so the schema is that of a single value.
+impl<U> JsonSchema1IgnoredFields for crate::CollectAll<U>
+where
+	U: crate::SerializeIgnoredFields,
+	U::Value: IntoIterator,
+	<U::Value as IntoIterator>::Item: schemars1::JsonSchema,
+{
+	fn value_schema(gen: &mut SchemaGenerator) -> Schema {
+		gen.subschema_for::<<U::Value as IntoIterator>::Item>()
+	}
+}
@@ -12,6 +12,14 @@ impl<'de> crate::DeserializeIgnoredFields<'de> for serde_json::Map<String, serde
 			Entry::Occupied(x) => Err(E::custom(format!("duplicate field: {:?}", x.key()))),
 		}
 	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		serde_json::Map::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
 }
 
 impl crate::SerializeIgnoredFields for serde_json::Map<String, serde_json::Value> {
@@ -26,3 +34,77 @@ impl crate::SerializeIgnoredFields for serde_json::Map<String, serde_json::Value
 		serde_json::Map::iter(self)
 	}
 }
+
+/// Collects ignored fields in a [`serde_json::Value::Object`].
+///
+/// The default value is [`serde_json::Value::Null`], which is turned into an object when the first field is inserted.
+/// Inserting a field into any other value is an error.
+impl<'de> crate::DeserializeIgnoredFields<'de> for serde_json::Value {
+	type Key = String;
+	type Value = serde_json::Value;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		if self.is_null() {
+			*self = serde_json::Value::Object(serde_json::Map::new());
+		}
+		match self {
+			serde_json::Value::Object(map) => crate::DeserializeIgnoredFields::insert(map, key, value),
+			other => Err(E::custom(format!(
+				"can not insert ignored field {key:?} into {}: expected a JSON object",
+				kind(other)
+			))),
+		}
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		self.as_object_mut()?.get_mut(key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+/// Serializes the fields of a [`serde_json::Value::Object`].
+///
+/// [`serde_json::Value::Null`] has no ignored fields.
+/// Serializing any other value is an error.
+impl crate::SerializeIgnoredFields for serde_json::Value {
+	type Key = String;
+	type Value = serde_json::Value;
+
+	fn len(&self) -> usize {
+		match self {
+			serde_json::Value::Null => 0,
+			serde_json::Value::Object(map) => map.len(),
+			// Count other values as a field, so they are not silently skipped but reported by `check()`.
+			_ => 1,
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		self.as_object().into_iter().flat_map(|map| map.iter())
+	}
+
+	fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+		match self {
+			serde_json::Value::Null | serde_json::Value::Object(_) => Ok(()),
+			other => Err(E::custom(format!(
+				"invalid type for ignored fields: expected a JSON object, found {}",
+				kind(other)
+			))),
+		}
+	}
+}
+
+/// Describe the kind of a JSON value for error messages.
+fn kind(value: &serde_json::Value) -> &'static str {
+	match value {
+		serde_json::Value::Null => "null",
+		serde_json::Value::Bool(_) => "a boolean",
+		serde_json::Value::Number(_) => "a number",
+		serde_json::Value::String(_) => "a string",
+		serde_json::Value::Array(_) => "an array",
+		serde_json::Value::Object(_) => "an object",
+	}
+}
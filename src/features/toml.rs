@@ -12,6 +12,14 @@ impl<'de> crate::DeserializeIgnoredFields<'de> for toml::Table {
 			Entry::Occupied(x) => Err(E::custom(format!("duplicate field: {:?}", x.key()))),
 		}
 	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		toml::Table::get_mut(self, key)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
 }
 
 impl crate::SerializeIgnoredFields for toml::Table {
@@ -0,0 +1,223 @@
+use std::collections::BTreeSet;
+
+use crate::path::key_name;
+
+/// Get the names of the fields of a value that serializes as a map, struct or struct variant.
+///
+/// Only the keys are inspected: the values of the fields are not serialized.
+/// Keys that can not be represented as a string are left out.
+///
+/// Returns [`None`] if the value does not serialize as a map, struct or struct variant.
+pub(crate) fn field_names<T: ?Sized + serde::Serialize>(value: &T) -> Option<BTreeSet<String>> {
+	value.serialize(FieldNameSerializer).ok()
+}
+
+/// Error for [`FieldNameSerializer`].
+///
+/// The serializer only has to report failure, so the error carries no information.
+#[derive(Debug)]
+struct NoFieldNames;
+
+impl std::fmt::Display for NoFieldNames {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("value is not a map or struct")
+	}
+}
+
+impl std::error::Error for NoFieldNames {}
+
+impl serde::ser::Error for NoFieldNames {
+	fn custom<T: std::fmt::Display>(_msg: T) -> Self {
+		Self
+	}
+}
+
+/// Serializer that collects the field names of a map, struct or struct variant.
+struct FieldNameSerializer;
+
+/// Implement serialize functions for types that are not a map or struct by returning an error.
+macro_rules! no_field_names {
+	($($ident:ident: $type:ty),* $(,)?) => {
+		$(
+			fn $ident(self, _v: $type) -> Result<Self::Ok, Self::Error> {
+				Err(NoFieldNames)
+			}
+		)*
+	};
+}
+
+impl serde::Serializer for FieldNameSerializer {
+	type Error = NoFieldNames;
+	type Ok = BTreeSet<String>;
+	type SerializeMap = FieldNames;
+	type SerializeSeq = serde::ser::Impossible<BTreeSet<String>, NoFieldNames>;
+	type SerializeStruct = FieldNames;
+	type SerializeStructVariant = FieldNames;
+	type SerializeTuple = serde::ser::Impossible<BTreeSet<String>, NoFieldNames>;
+	type SerializeTupleStruct = serde::ser::Impossible<BTreeSet<String>, NoFieldNames>;
+	type SerializeTupleVariant = serde::ser::Impossible<BTreeSet<String>, NoFieldNames>;
+
+	no_field_names!(
+		serialize_bool: bool,
+		serialize_i8: i8,
+		serialize_i16: i16,
+		serialize_i32: i32,
+		serialize_i64: i64,
+		serialize_i128: i128,
+		serialize_u8: u8,
+		serialize_u16: u16,
+		serialize_u32: u32,
+		serialize_u64: u64,
+		serialize_u128: u128,
+		serialize_f32: f32,
+		serialize_f64: f64,
+		serialize_char: char,
+		serialize_str: &str,
+		serialize_bytes: &[u8],
+	);
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_some<T: ?Sized + serde::Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+	) -> Result<Self::Ok, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Err(NoFieldNames)
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(FieldNames::default())
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(FieldNames::default())
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(FieldNames::default())
+	}
+}
+
+/// Collector for the field names of a map, struct or struct variant.
+#[derive(Default)]
+struct FieldNames(BTreeSet<String>);
+
+impl serde::ser::SerializeMap for FieldNames {
+	type Error = NoFieldNames;
+	type Ok = BTreeSet<String>;
+
+	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.0.extend(key_name(key));
+		Ok(())
+	}
+
+	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, _value: &T) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+impl serde::ser::SerializeStruct for FieldNames {
+	type Error = NoFieldNames;
+	type Ok = BTreeSet<String>;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		_value: &T,
+	) -> Result<(), Self::Error> {
+		self.0.insert(key.into());
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+impl serde::ser::SerializeStructVariant for FieldNames {
+	type Error = NoFieldNames;
+	type Ok = BTreeSet<String>;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		_value: &T,
+	) -> Result<(), Self::Error> {
+		self.0.insert(key.into());
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
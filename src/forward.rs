@@ -0,0 +1,195 @@
+/// Forward the methods of [`DeserializeIgnoredFields`](crate::DeserializeIgnoredFields) to a field of a wrapper.
+///
+/// This must be used inside the `impl` block of the trait, after the associated types.
+/// All methods are forwarded, except the ones listed after `except`, which the wrapper implements itself.
+/// This macro is the only place that lists all methods of the trait,
+/// so a method that is added to the trait is forwarded by every wrapper.
+macro_rules! forward_deserialize_ignored_fields {
+	($field:tt $(, except $($skip:ident),+)? $(,)?) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@insert $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@get_mut $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@supports_get_mut $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@deserialize_key $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@deserialize_value $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@set_known_fields $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@set_name $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@set_known_variants $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@set_position $field; $($($skip)+)?);
+		$crate::forward::forward_deserialize_ignored_fields!(@continue_from $field; $($($skip)+)?);
+	};
+	(@insert $field:tt; insert $($rest:ident)*) => {};
+	(@insert $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@insert $field; $($rest)*);
+	};
+	(@insert $field:tt;) => {
+		fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+			self.$field.insert(key, value)
+		}
+	};
+	(@get_mut $field:tt; get_mut $($rest:ident)*) => {};
+	(@get_mut $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@get_mut $field; $($rest)*);
+	};
+	(@get_mut $field:tt;) => {
+		fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+			self.$field.get_mut(key)
+		}
+	};
+	(@supports_get_mut $field:tt; supports_get_mut $($rest:ident)*) => {};
+	(@supports_get_mut $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@supports_get_mut $field; $($rest)*);
+	};
+	(@supports_get_mut $field:tt;) => {
+		fn supports_get_mut(&self) -> bool {
+			self.$field.supports_get_mut()
+		}
+	};
+	(@deserialize_key $field:tt; deserialize_key $($rest:ident)*) => {};
+	(@deserialize_key $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@deserialize_key $field; $($rest)*);
+	};
+	(@deserialize_key $field:tt;) => {
+		fn deserialize_key<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Key, D::Error> {
+			self.$field.deserialize_key(deserializer)
+		}
+	};
+	(@deserialize_value $field:tt; deserialize_value $($rest:ident)*) => {};
+	(@deserialize_value $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@deserialize_value $field; $($rest)*);
+	};
+	(@deserialize_value $field:tt;) => {
+		fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error> {
+			self.$field.deserialize_value(deserializer)
+		}
+	};
+	(@set_known_fields $field:tt; set_known_fields $($rest:ident)*) => {};
+	(@set_known_fields $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@set_known_fields $field; $($rest)*);
+	};
+	(@set_known_fields $field:tt;) => {
+		fn set_known_fields(&mut self, fields: &'static [&'static str]) {
+			self.$field.set_known_fields(fields)
+		}
+	};
+	(@set_name $field:tt; set_name $($rest:ident)*) => {};
+	(@set_name $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@set_name $field; $($rest)*);
+	};
+	(@set_name $field:tt;) => {
+		fn set_name(&mut self, name: &'static str) {
+			self.$field.set_name(name)
+		}
+	};
+	(@set_known_variants $field:tt; set_known_variants $($rest:ident)*) => {};
+	(@set_known_variants $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@set_known_variants $field; $($rest)*);
+	};
+	(@set_known_variants $field:tt;) => {
+		fn set_known_variants(&mut self, variants: &'static [&'static str]) {
+			self.$field.set_known_variants(variants)
+		}
+	};
+	(@set_position $field:tt; set_position $($rest:ident)*) => {};
+	(@set_position $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@set_position $field; $($rest)*);
+	};
+	(@set_position $field:tt;) => {
+		fn set_position(&mut self, position: $crate::FieldPosition<'_>) {
+			self.$field.set_position(position)
+		}
+	};
+	(@continue_from $field:tt; continue_from $($rest:ident)*) => {};
+	(@continue_from $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_deserialize_ignored_fields!(@continue_from $field; $($rest)*);
+	};
+	(@continue_from $field:tt;) => {
+		fn continue_from(&mut self, previous: &Self) {
+			self.$field.continue_from(&previous.$field)
+		}
+	};
+}
+
+pub(crate) use forward_deserialize_ignored_fields;
+
+/// Forward the methods of [`SerializeIgnoredFields`](crate::SerializeIgnoredFields) to a field of a wrapper.
+///
+/// This must be used inside the `impl` block of the trait, after the associated types.
+/// All methods are forwarded, except the ones listed after `except`, which the wrapper implements itself.
+/// This macro is the only place that lists all methods of the trait,
+/// so a method that is added to the trait is forwarded by every wrapper.
+macro_rules! forward_serialize_ignored_fields {
+	($field:tt $(, except $($skip:ident),+)? $(,)?) => {
+		$crate::forward::forward_serialize_ignored_fields!(@len $field; $($($skip)+)?);
+		$crate::forward::forward_serialize_ignored_fields!(@is_empty $field; $($($skip)+)?);
+		$crate::forward::forward_serialize_ignored_fields!(@iter $field; $($($skip)+)?);
+		$crate::forward::forward_serialize_ignored_fields!(@position $field; $($($skip)+)?);
+		$crate::forward::forward_serialize_ignored_fields!(@collision_policy $field; $($($skip)+)?);
+		$crate::forward::forward_serialize_ignored_fields!(@ron_struct $field; $($($skip)+)?);
+		$crate::forward::forward_serialize_ignored_fields!(@check $field; $($($skip)+)?);
+	};
+	(@len $field:tt; len $($rest:ident)*) => {};
+	(@len $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@len $field; $($rest)*);
+	};
+	(@len $field:tt;) => {
+		fn len(&self) -> usize {
+			self.$field.len()
+		}
+	};
+	(@is_empty $field:tt; is_empty $($rest:ident)*) => {};
+	(@is_empty $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@is_empty $field; $($rest)*);
+	};
+	(@is_empty $field:tt;) => {
+		fn is_empty(&self) -> bool {
+			self.$field.is_empty()
+		}
+	};
+	(@iter $field:tt; iter $($rest:ident)*) => {};
+	(@iter $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@iter $field; $($rest)*);
+	};
+	(@iter $field:tt;) => {
+		fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+			self.$field.iter()
+		}
+	};
+	(@position $field:tt; position $($rest:ident)*) => {};
+	(@position $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@position $field; $($rest)*);
+	};
+	(@position $field:tt;) => {
+		fn position(&self, key: &Self::Key) -> $crate::FieldPosition<'_> {
+			self.$field.position(key)
+		}
+	};
+	(@collision_policy $field:tt; collision_policy $($rest:ident)*) => {};
+	(@collision_policy $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@collision_policy $field; $($rest)*);
+	};
+	(@collision_policy $field:tt;) => {
+		fn collision_policy(&self) -> $crate::CollisionPolicy {
+			self.$field.collision_policy()
+		}
+	};
+	(@ron_struct $field:tt; ron_struct $($rest:ident)*) => {};
+	(@ron_struct $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@ron_struct $field; $($rest)*);
+	};
+	(@ron_struct $field:tt;) => {
+		fn ron_struct(&self) -> bool {
+			self.$field.ron_struct()
+		}
+	};
+	(@check $field:tt; check $($rest:ident)*) => {};
+	(@check $field:tt; $skip:ident $($rest:ident)*) => {
+		$crate::forward::forward_serialize_ignored_fields!(@check $field; $($rest)*);
+	};
+	(@check $field:tt;) => {
+		fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+			self.$field.check()
+		}
+	};
+}
+
+pub(crate) use forward_serialize_ignored_fields;
@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+use crate::path::PathSegment;
+
 /// A type used to tore keys during deserialization.
 ///
 /// Not exposed directly to the user.
@@ -41,6 +43,39 @@ impl<'de> Key<'de> {
 			_error: PhantomData,
 		}
 	}
+
+	/// Get the key as string slice, if it is a string.
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Key::Str(x) => Some(x),
+			Key::String(x) => Some(x),
+			Key::Some(x) => x.as_str(),
+			Key::NewTypeStruct(x) => x.as_str(),
+			_ => None,
+		}
+	}
+
+	/// Get the path segment for the key, if it can be represented as one.
+	pub fn to_path_segment(&self) -> Option<PathSegment> {
+		match self {
+			Key::I8(x) => Some(PathSegment::Field(x.to_string())),
+			Key::I16(x) => Some(PathSegment::Field(x.to_string())),
+			Key::I32(x) => Some(PathSegment::Field(x.to_string())),
+			Key::I64(x) => Some(PathSegment::Field(x.to_string())),
+			Key::I128(x) => Some(PathSegment::Field(x.to_string())),
+			Key::U8(x) => Some(PathSegment::Field(x.to_string())),
+			Key::U16(x) => Some(PathSegment::Field(x.to_string())),
+			Key::U32(x) => Some(PathSegment::Field(x.to_string())),
+			Key::U64(x) => Some(PathSegment::Field(x.to_string())),
+			Key::U128(x) => Some(PathSegment::Field(x.to_string())),
+			Key::Char(x) => Some(PathSegment::Field(x.to_string())),
+			Key::Str(x) => Some(PathSegment::Field(x.to_string())),
+			Key::String(x) => Some(PathSegment::Field(x.clone())),
+			Key::Some(x) => x.to_path_segment(),
+			Key::NewTypeStruct(x) => x.to_path_segment(),
+			_ => None,
+		}
+	}
 }
 
 /// Deserializer that consumes a [`Key`].
@@ -59,8 +59,56 @@
 //! # }
 //! ```
 //!
+//! If you *are* in control of the type but want to avoid the buffering done by `#[serde(flatten)]`,
+//! you can enable the `derive` feature and use the [`PreserveIgnored`] derive macro.
+//! It stores the ignored fields in a field of the struct itself, using the same mechanism as [`PreserveIgnoredFields`].
+//! The [`preserve_ignored`] attribute adds the serde attributes that the derive macro needs.
+//!
+//! The ignored fields can be stored in any type that implements [`DeserializeIgnoredFields`] and [`SerializeIgnoredFields`].
+//! This crate implements them for `BTreeMap<K, V>`, `HashMap<K, V, S>` and `Vec<(K, V)>`.
+//! The `Vec<(K, V)>` keeps the ignored fields in their original order, including duplicate keys.
+//! If you only need to know which fields were ignored, you can use [`IgnoredFieldNames`].
+//! It skips the values of ignored fields instead of storing them,
+//! and it can suggest which known field an ignored field was probably meant to be.
+//! For format independent code, you can use the [`Map`] of [`Value`]s provided by this crate.
+//! It can capture ignored fields from any self-describing format and serialize them again with any other format.
+//! If you enable the `serde_json`, `serde_yaml`, `serde_yml`, `toml`, `ron` or `bson` feature, they are also implemented for the map type of that crate.
+//! With the `serde_json` feature, they are also implemented for `serde_json::Value`, which holds the ignored fields as an object.
+//! Serializing a `serde_json::Value` that is not an object or `null` reports an error.
+//! With the `ciborium` feature, [`SerializeIgnoredFields`] is implemented for a `ciborium::Value` holding a CBOR map.
+//! To deserialize CBOR fields with any type of key, such as integers or byte strings, use `Vec<(ciborium::Value, ciborium::Value)>`.
+//! Likewise, the `rmpv` feature implements it for a `rmpv::Value` holding a MessagePack map,
+//! and `Vec<(rmpv::Value, rmpv::Value)>` can deserialize MessagePack fields with any type of key.
+//! The `ron` feature also implements [`SerializeIgnoredFields`] for a `ron::Value` holding a RON map.
+//! To write ignored JSON fields back exactly as they were read, enable the `raw_value` feature of `serde_json`
+//! and use `Box<serde_json::value::RawValue>` or `&'de RawValue` as value type,
+//! for example in a `Vec<(String, Box<RawValue>)>`.
+//! That keeps number formatting, string escapes and whitespace inside the values unchanged.
+//! If you enable the `indexmap` feature, they are implemented for `IndexMap<K, V, S>`, which keeps the ignored fields in their original order.
+//!
+//! Except for `Vec<(K, V)>`, these types report an error when the same ignored field appears more than once.
+//! To handle duplicate keys differently, wrap them in [`FirstWins`], [`LastWins`] or [`CollectAll`].
+//! To limit the number of ignored fields, their nesting depth and their total size when deserializing untrusted input,
+//! wrap them in [`Limited`].
+//! To also record the name, known fields and variants of the deserialized struct or enum, wrap them in [`WithMetadata`].
+//! When serialized, ignored fields are normally added after all other fields.
+//! To put them back between the other fields at the position where they were read, wrap them in [`KeepOrder`].
+//! If the value has a field with the same key as an ignored field, serializing it reports an error.
+//! To skip the ignored field or the field of the value instead, wrap the ignored fields in [`ValueWins`] or [`IgnoredWins`].
+//!
+//! [`PreserveIgnoredFields`] only captures the ignored fields of the top-level struct or map.
+//! If you also want to preserve the ignored fields of nested structs and maps, you can use [`PreserveIgnoredFieldsDeep`].
+//! It stores the ignored fields of each nesting level by [`FieldPath`] and adds them back at the same place when serialized.
+//!
+//! If you want to reject ignored fields instead, you can wrap the type in [`DenyIgnoredFields`].
+//! Unlike `#[serde(deny_unknown_fields)]`, it works for types you do not control
+//! and the error lists all ignored fields instead of only the first one.
+//!
 //! If you enable the `schemars` feature, [`PreserveIgnoredFields<T, U>`] implements the [`schemars::JsonSchema`] trait.
-//! It forwards directly to the [`schemars::JsonSchema`] implementation of `T`.
+//! It uses the schema of `T`, with the schema of the ignored field values as `additionalProperties`.
+//! The ignored fields type must implement [`JsonSchemaIgnoredFields`] to describe its values.
+//! The `schemars1` feature does the same for `schemars` 1.x,
+//! where the ignored fields type must implement [`JsonSchema1IgnoredFields`] instead.
 //!
 //! # Limitations
 //! Because `serde` does not provide first class support for capturing ignored fields, there are some limitations.
@@ -72,15 +120,39 @@
 //!
 //! In [`serde`] terms: the [`serde::Deserializer`] must support [`serde::Deserializer::deserialize_any()`].
 //!
+//! Binary formats like CBOR can have map keys of any type, not just strings.
+//! For formats that are not human readable, the keys of a struct are therefore deserialized with [`serde::Deserializer::deserialize_any()`],
+//! so that integer or byte string keys can be captured as ignored field.
+//! Although `serde` normally reads an integer key as the index of a field, the integer keys of a struct are always captured as ignored field.
+//! This means that data which encodes the fields of a struct by their index can not be deserialized.
+//!
 //! ## Serialize/Deserialize implementations
 //! Secondly, the type `T` being (de)serialized must be represented as a key/value map,
 //! and it must call [`serde::Deserializer::deserialize_ignored_any()`] to deserialize ignored fields.
 //! It must not produce an error when encountering an unknown field (so the type must not use `#[serde(deny_unknown_fields)]`).
 //!
-//! In particular, this means that it will not work for *externally* tagged enums, *internally* tagged enums and *untagged* enums.
-//! Externally tagged enums are not always serialized as key/value maps (the serialization format controls their layout).
-//! Internally and untagged enums have to look at fields before knowing which of the fields are actually going to be ignored.
-//! This crate *does* work with adjectently tagged enums.
+//! In particular, this means that it will not work for *internally* tagged enums and *untagged* enums.
+//! They have to look at fields before knowing which of the fields are actually going to be ignored.
+//! Deserializing them from a map with [`PreserveIgnoredFields`] is an error, so their ignored fields are not silently lost.
+//! For internally tagged and untagged enums, you can use [`PreserveIgnoredFieldsBuffered`] instead.
+//!
+//! This crate *does* work with adjectently tagged enums and with *externally* tagged enums.
+//! For externally tagged enums, the ignored fields are captured from the struct inside a struct or newtype variant.
+//! When serialized, a struct variant with ignored fields is serialized as a newtype variant containing a map,
+//! since the fields of a struct variant can only have static names.
+//! For the same reason, a struct with ignored fields is serialized as a map.
+//! Self-describing formats represent both in the same way.
+//! Unit and tuple variants can only be serialized if there are no ignored fields.
+//!
+//! RON does distinguish structs from maps, so RON can not deserialize such a struct again.
+//! With the `ron` feature, wrap the ignored fields in [`AsRonStruct`] to write structs and struct variants with ignored fields
+//! as RON text in struct syntax, with the ignored fields added as struct fields.
+//! That text is not pretty printed and does not use RON extensions.
+//! If an ignored field has a key that is not a string, the struct is serialized as a map instead.
+//!
+//! The type `T` can also be an `Option`, a newtype struct or a `#[serde(transparent)]` struct around such a map or struct.
+//! The ignored fields are captured from and added to the inner value.
+//! A `None` or unit value can only be serialized if there are no ignored fields.
 //!
 //! It also means that it will not work for types that first deserialize into something like [`serde_json::Value`] before processing the value further.
 //! When deserialized, the [`serde_json::Value`] uses all fields.
@@ -88,16 +160,134 @@
 //!
 //! In summary:
 //! Using [`PreserveIgnoredFields`] with structs that use the standard serde derive macros from [`serde`] will work, as long as you did not use `#[serde(deny_unknown_fields)]`.
-//! Using it with enums that use the standard derive macros will only work if they are *adjectently tagged* (they have a serde `tag = "..."` *and* `content = "..."` attribute).
+//! Using it with enums that use the standard derive macros will work if they are *externally tagged* (the default)
+//! or *adjectently tagged* (they have a serde `tag = "..."` *and* `content = "..."` attribute).
+//! Use [`PreserveIgnoredFieldsBuffered`] for *internally tagged* enums (they have only a serde `tag = "..."` attribute) and for *untagged* enums.
 
 #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
 #![cfg_attr(feature = "doc-cfg", feature(doc_cfg))]
 
+mod buffered;
+mod collections;
+mod collisions;
+mod deep;
+mod deny;
 mod deserialize;
+mod duplicates;
 mod features;
+mod field_names;
+mod forward;
 mod key;
+mod limits;
+mod metadata;
+mod order;
+mod path;
 mod serialize;
+mod suggest;
+mod value;
+
+pub use collections::IgnoredFieldNames;
+pub use collisions::{CollisionPolicy, IgnoredWins, ValueWins};
+pub use duplicates::{CollectAll, FirstWins, LastWins};
+#[cfg(feature = "ron")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "ron")))]
+pub use features::AsRonStruct;
+#[cfg(feature = "schemars1")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "schemars1")))]
+pub use features::JsonSchema1IgnoredFields;
+#[cfg(feature = "schemars")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "schemars")))]
+pub use features::JsonSchemaIgnoredFields;
+pub use limits::Limited;
+pub use metadata::{Metadata, WithMetadata};
+pub use order::{FieldPosition, KeepOrder};
+pub use path::{FieldPath, PathSegment};
+pub use value::{Map, MapKey, Value};
+
+/// Derive [`serde::Deserialize`] and [`serde::Serialize`] for a struct that keeps its own ignored fields.
+///
+/// Mark one field of the struct with `#[ignored_fields]`.
+/// The type of that field must implement [`DeserializeIgnoredFields`] and [`SerializeIgnoredFields`].
+/// When deserialized, all ignored fields are stored in the marked field,
+/// and when serialized they are added back to the struct.
+///
+/// The regular serde derive macros must also be used, but with `#[serde(remote = "Self")]` on the struct and `#[serde(skip)]` on the marked field.
+/// That tells serde to generate inherent `deserialize()` and `serialize()` functions instead of trait implementations.
+/// The [`preserve_ignored`] attribute can add these attributes for you.
+/// This macro then implements the [`serde::Deserialize`] and [`serde::Serialize`] traits by wrapping those functions,
+/// just like [`PreserveIgnoredFields`] does.
+/// Unlike `#[serde(flatten)]`, this does not buffer the input.
+///
+/// The struct must not use `#[serde(deny_unknown_fields)]`, since that rejects the fields that would be captured.
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// # use assert2::assert;
+/// use serde_ignored_fields::PreserveIgnored;
+///
+/// #[derive(serde::Deserialize, serde::Serialize, PreserveIgnored)]
+/// #[serde(remote = "Self")]
+/// struct Thing {
+///   name: String,
+///
+///   #[ignored_fields]
+///   #[serde(skip)]
+///   extra: serde_yaml::Mapping,
+/// }
+///
+/// let thing: Thing = serde_yaml::from_str("
+///   name: Turbo Encabulator
+///   casing: malleable logarithmic
+/// ")?;
+///
+/// assert!(thing.name == "Turbo Encabulator");
+/// assert!(thing.extra["casing"] == "malleable logarithmic");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "derive")))]
+pub use serde_ignored_fields_derive::PreserveIgnored;
+
+/// Prepare a struct for the [`PreserveIgnored`] derive macro.
+///
+/// This adds `#[serde(remote = "Self")]` to the struct, `#[serde(skip)]` to the field marked with `#[ignored_fields]`
+/// and `#[derive(PreserveIgnored)]`, unless they are already present.
+/// The struct still needs the regular serde derive macros.
+///
+/// The attribute must be placed above the `#[derive(...)]` attribute, so that it runs before the derive macros.
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// # use assert2::assert;
+/// #[serde_ignored_fields::preserve_ignored]
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+///
+///   #[ignored_fields]
+///   extra: serde_yaml::Mapping,
+/// }
+///
+/// let thing: Thing = serde_yaml::from_str("
+///   name: Turbo Encabulator
+///   casing: malleable logarithmic
+/// ")?;
+///
+/// assert!(thing.name == "Turbo Encabulator");
+/// assert!(thing.extra["casing"] == "malleable logarithmic");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "derive")))]
+pub use serde_ignored_fields_derive::preserve_ignored;
+
+#[doc(hidden)]
+pub mod __private {
+	pub use serde;
+}
 
 /// Wrapper to preserve ignored fields.
 ///
@@ -110,7 +300,8 @@ mod serialize;
 /// Be sure the read the [main library documentation](crate) about the limitations.
 ///
 /// If you enable the `schemars` feature, this type implements the [`schemars::JsonSchema`] trait.
-/// The implementation forwards directly to the implementation of `T`.
+/// The implementation uses the schema of `T`, with the schema of the ignored field values as `additionalProperties`.
+/// The `schemars1` feature implements the `schemars` 1.x version of the trait in the same way.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct PreserveIgnoredFields<T, U> {
 	/// The wrapped value.
@@ -133,6 +324,187 @@ impl<T, U: Default> From<T> for PreserveIgnoredFields<T, U> {
 	}
 }
 
+/// Wrapper to preserve ignored fields of the wrapped type and all nested structs and maps.
+///
+/// This works like [`PreserveIgnoredFields`], except that the ignored fields are also captured for nested values.
+/// The ignored fields are stored per nesting level in the [`ignored_fields`][Self::ignored_fields] field,
+/// keyed by the [`FieldPath`] of the struct or map they were found in.
+/// The ignored fields of the top-level value are stored under the empty path.
+/// Levels without ignored fields do not have an entry.
+///
+/// When serialized, the ignored fields are added back to the struct or map at the same path.
+/// The path of an element of a sequence is the path of the sequence followed by the index of the element.
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// # use assert2::assert;
+/// use serde_ignored_fields::{FieldPath, PreserveIgnoredFieldsDeep};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+///   base_plate: BasePlate,
+/// }
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct BasePlate {
+///   material: String,
+/// }
+///
+/// let thing: PreserveIgnoredFieldsDeep<Thing, serde_yaml::Mapping> = serde_yaml::from_str("
+///   name: Turbo Encabulator
+///   base_plate:
+///     prefabulated: true
+///     material: aluminite
+///   casing: malleable logarithmic
+/// ")?;
+///
+/// assert!(thing.value.base_plate.material == "aluminite");
+/// assert!(thing.ignored_fields[&FieldPath::new()]["casing"] == "malleable logarithmic");
+/// assert!(thing.ignored_fields[&FieldPath::from_iter(["base_plate"])]["prefabulated"] == true);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Be sure the read the [main library documentation](crate) about the limitations.
+/// They apply to every nested value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreserveIgnoredFieldsDeep<T, U> {
+	/// The wrapped value.
+	pub value: T,
+
+	/// The ignored fields of all nesting levels, keyed by path.
+	pub ignored_fields: std::collections::BTreeMap<FieldPath, U>,
+}
+
+impl<T, U> PreserveIgnoredFieldsDeep<T, U> {
+	/// Create a new [`PreserveIgnoredFieldsDeep`] struct from a wrapped value and the ignored fields.
+	pub fn new(value: T, ignored_fields: std::collections::BTreeMap<FieldPath, U>) -> Self {
+		Self { value, ignored_fields }
+	}
+}
+
+impl<T: Default, U> Default for PreserveIgnoredFieldsDeep<T, U> {
+	fn default() -> Self {
+		Self::from(T::default())
+	}
+}
+
+impl<T, U> From<T> for PreserveIgnoredFieldsDeep<T, U> {
+	fn from(value: T) -> Self {
+		Self::new(value, Default::default())
+	}
+}
+
+/// Wrapper to preserve ignored fields of types that buffer their input before deserializing it.
+///
+/// This works like [`PreserveIgnoredFields`], but it also supports types that look at all fields before deciding which fields they use,
+/// such as internally tagged enums (`#[serde(tag = "...")]`) and untagged enums (`#[serde(untagged)]`).
+/// Those types never report which fields they ignore, so instead the input is buffered in memory,
+/// the value is deserialized from the buffer and then serialized again to find out which fields it used.
+/// All other top-level fields of the input are stored in the [`ignored_fields`][Self::ignored_fields] field.
+///
+/// This means that the wrapped type must implement [`serde::Serialize`] for this type to implement [`serde::Deserialize`].
+/// It also means that fields are only recognized as used if `T` serializes them again with the same key.
+/// Fields that are read through an alias or that are skipped during serialization are treated as ignored fields.
+/// Because the input is buffered, `T` can not borrow data from the input.
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::PreserveIgnoredFieldsBuffered;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[serde(tag = "type")]
+/// enum Thing {
+///   Encabulator { name: String },
+/// }
+///
+/// let thing: PreserveIgnoredFieldsBuffered<Thing, serde_yaml::Mapping> = serde_yaml::from_str("
+///   type: Encabulator
+///   name: Turbo Encabulator
+///   casing: malleable logarithmic
+/// ")?;
+///
+/// let_assert!(Thing::Encabulator { name } = &thing.value);
+/// assert!(name == "Turbo Encabulator");
+/// assert!(thing.ignored_fields["casing"] == "malleable logarithmic");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Serialization works the same as for [`PreserveIgnoredFields`].
+/// For untagged enums, the ignored fields are added to whichever variant is serialized.
+/// Variants that are not serialized as a map can only be serialized if there are no ignored fields.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreserveIgnoredFieldsBuffered<T, U> {
+	/// The wrapped value.
+	pub value: T,
+
+	/// The ignored fields.
+	pub ignored_fields: U,
+}
+
+impl<T, U> PreserveIgnoredFieldsBuffered<T, U> {
+	/// Create a new [`PreserveIgnoredFieldsBuffered`] struct from a wrapped value and the ignored fields.
+	pub fn new(value: T, ignored_fields: U) -> Self {
+		Self { value, ignored_fields }
+	}
+}
+
+impl<T, U: Default> From<T> for PreserveIgnoredFieldsBuffered<T, U> {
+	fn from(value: T) -> Self {
+		Self::new(value, U::default())
+	}
+}
+
+/// Wrapper to reject ignored fields, with an error that lists all of them.
+///
+/// This is similar to `#[serde(deny_unknown_fields)]`, except that it also works for types you do not control.
+/// Deserialization does not stop at the first unknown field:
+/// the wrapped type is deserialized completely and then an error is reported that lists every ignored field.
+/// Just like [`PreserveIgnoredFields`], only the fields of the top-level struct or map are checked.
+///
+/// The error is only reported if the wrapped type could be deserialized.
+/// Otherwise, the error of the wrapped type is returned.
+///
+/// When serialized, the wrapped value is serialized directly.
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::DenyIgnoredFields;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+/// }
+///
+/// let_assert!(Err(e) = serde_yaml::from_str::<DenyIgnoredFields<Thing>>("
+///   name: Turbo Encabulator
+///   casing: malleable logarithmic
+///   base_plate: prefabulated aluminite
+/// "));
+/// assert!(e.to_string().starts_with("unknown fields: `casing`, `base_plate`"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DenyIgnoredFields<T> {
+	/// The wrapped value.
+	pub value: T,
+}
+
+impl<T> DenyIgnoredFields<T> {
+	/// Create a new [`DenyIgnoredFields`] struct from a wrapped value.
+	pub fn new(value: T) -> Self {
+		Self { value }
+	}
+}
+
+impl<T> From<T> for DenyIgnoredFields<T> {
+	fn from(value: T) -> Self {
+		Self::new(value)
+	}
+}
+
 /// Trait for types that can collect ignored fields during deserialization.
 pub trait DeserializeIgnoredFields<'de>: Default + std::fmt::Debug {
 	/// The type of the key for the ignored fields.
@@ -143,6 +515,92 @@ pub trait DeserializeIgnoredFields<'de>: Default + std::fmt::Debug {
 
 	/// Insert an ignored field.
 	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E>;
+
+	/// Get a mutable reference to the value of an ignored field that was inserted before.
+	///
+	/// Wrappers like [`LastWins`] use this to update duplicate fields in place,
+	/// so the position of the field and any other state of the collection are kept.
+	/// If the key was inserted more than once, the value of the first occurrence is returned.
+	///
+	/// The default implementation returns `None`, so duplicate keys are always passed to [`Self::insert()`].
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		let _ = key;
+		None
+	}
+
+	/// Check if [`Self::get_mut()`] can find the ignored fields that were inserted before.
+	///
+	/// Wrappers like [`FirstWins`] need this to detect duplicate keys, and report an error if it returns `false`.
+	/// Implementations that override [`Self::get_mut()`] should also override this to return `true`.
+	///
+	/// The default implementation returns `false`.
+	fn supports_get_mut(&self) -> bool {
+		false
+	}
+
+	/// Deserialize the key of an ignored field.
+	///
+	/// The default implementation uses the [`serde::Deserialize`] implementation of [`Self::Key`].
+	/// Wrappers like [`Limited`] override it to inspect the data before it is stored.
+	fn deserialize_key<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Key, D::Error> {
+		serde::Deserialize::deserialize(deserializer)
+	}
+
+	/// Deserialize the value of an ignored field.
+	///
+	/// The default implementation uses the [`serde::Deserialize`] implementation of [`Self::Value`].
+	/// Wrappers like [`Limited`] override it to inspect the data before it is stored.
+	fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error> {
+		serde::Deserialize::deserialize(deserializer)
+	}
+
+	/// Set the names of the known fields of the struct that the ignored fields belong to.
+	///
+	/// This is called with the field list that `serde` passes to [`serde::Deserializer::deserialize_struct()`],
+	/// before any ignored fields are inserted.
+	/// The default implementation does nothing.
+	fn set_known_fields(&mut self, fields: &'static [&'static str]) {
+		let _ = fields;
+	}
+
+	/// Set the name of the struct or enum that the ignored fields belong to.
+	///
+	/// This is called with the name that `serde` passes to [`serde::Deserializer::deserialize_struct()`]
+	/// or [`serde::Deserializer::deserialize_enum()`], before any ignored fields are inserted.
+	/// The default implementation does nothing.
+	fn set_name(&mut self, name: &'static str) {
+		let _ = name;
+	}
+
+	/// Set the names of the variants of the enum that the ignored fields belong to.
+	///
+	/// This is called with the variant list that `serde` passes to [`serde::Deserializer::deserialize_enum()`],
+	/// before any ignored fields are inserted.
+	/// The default implementation does nothing.
+	fn set_known_variants(&mut self, variants: &'static [&'static str]) {
+		let _ = variants;
+	}
+
+	/// Set the position of the next ignored field relative to the other fields of the map or struct.
+	///
+	/// This is called right before [`Self::insert()`] with the position of the ignored field:
+	/// [`FieldPosition::Start`] if no field was used by the deserialized type yet,
+	/// or [`FieldPosition::After`] with the key of the last field that was used.
+	/// If that key is not a string, the position is [`FieldPosition::End`].
+	/// The default implementation does nothing.
+	fn set_position(&mut self, position: FieldPosition<'_>) {
+		let _ = position;
+	}
+
+	/// Continue from the state of the ignored fields of another nesting level.
+	///
+	/// [`PreserveIgnoredFieldsDeep`] collects the ignored fields of each nesting level separately.
+	/// Whenever it switches to a different level, it calls this with the ignored fields of the level that was used before,
+	/// so wrappers like [`Limited`] can apply their limits to all levels together.
+	/// The default implementation does nothing.
+	fn continue_from(&mut self, previous: &Self) {
+		let _ = previous;
+	}
 }
 
 /// Trait for types that can be used to re-serialize ignored fields.
@@ -163,4 +621,73 @@ pub trait SerializeIgnoredFields {
 
 	/// Iterate over the ignored fields.
 	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)>;
+
+	/// Get the position of an ignored field relative to the other fields of the map or struct.
+	///
+	/// The default implementation returns [`FieldPosition::End`] for all fields,
+	/// so the ignored fields are added after all other fields.
+	fn position(&self, key: &Self::Key) -> FieldPosition<'_> {
+		let _ = key;
+		FieldPosition::End
+	}
+
+	/// Get what to do if the serialized value has a field with the same key as an ignored field.
+	///
+	/// The default implementation returns [`CollisionPolicy::Error`].
+	/// Wrap the ignored fields in [`ValueWins`] or [`IgnoredWins`] to use a different policy.
+	fn collision_policy(&self) -> CollisionPolicy {
+		CollisionPolicy::Error
+	}
+
+	/// Check if a struct or struct variant with these ignored fields is written in RON struct syntax.
+	///
+	/// The default implementation returns `false`, so the struct is written as a map.
+	/// With the `ron` feature, wrap the ignored fields in `AsRonStruct` to write RON structs instead.
+	/// This must only be used with a RON serializer.
+	fn ron_struct(&self) -> bool {
+		false
+	}
+
+	/// Check if the ignored fields can be serialized.
+	///
+	/// This is called before any ignored field is serialized.
+	/// It allows collections that can hold something other than a map of fields to report an error.
+	///
+	/// The default implementation always succeeds.
+	fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+		Ok(())
+	}
+}
+
+impl<T: ?Sized + SerializeIgnoredFields> SerializeIgnoredFields for &T {
+	type Key = T::Key;
+	type Value = T::Value;
+
+	fn len(&self) -> usize {
+		T::len(self)
+	}
+
+	fn is_empty(&self) -> bool {
+		T::is_empty(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		T::iter(self)
+	}
+
+	fn position(&self, key: &Self::Key) -> FieldPosition<'_> {
+		T::position(self, key)
+	}
+
+	fn collision_policy(&self) -> CollisionPolicy {
+		T::collision_policy(self)
+	}
+
+	fn ron_struct(&self) -> bool {
+		T::ron_struct(self)
+	}
+
+	fn check<E: serde::ser::Error>(&self) -> Result<(), E> {
+		T::check(self)
+	}
 }
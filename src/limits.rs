@@ -0,0 +1,424 @@
+use crate::forward::{forward_deserialize_ignored_fields, forward_serialize_ignored_fields};
+use crate::{DeserializeIgnoredFields, SerializeIgnoredFields};
+
+/// Wrapper for ignored fields that limits how much data is captured.
+///
+/// The limits are given as const generic parameters:
+/// * `MAX_FIELDS`: the maximum number of ignored fields.
+/// * `MAX_DEPTH`: the maximum nesting depth of the key and value of an ignored field.
+///   Each sequence or map adds one level, so a depth of 0 only allows plain values like numbers and strings.
+/// * `MAX_SIZE`: the maximum total size of all ignored keys and values, in bytes.
+///
+/// The size is an approximation: strings and byte arrays count their length,
+/// other primitive values count their size in memory and sequences, maps and empty values count as one byte.
+///
+/// Deserialization fails with an error as soon as one of the limits is exceeded,
+/// so data from untrusted sources can not make the ignored fields grow without bounds.
+/// With [`PreserveIgnoredFieldsDeep`](crate::PreserveIgnoredFieldsDeep),
+/// the limits apply to the ignored fields of all nesting levels together.
+/// Note that [`PreserveIgnoredFieldsBuffered`](crate::PreserveIgnoredFieldsBuffered) always buffers the entire input first,
+/// so the limits only apply to the ignored fields that are stored afterwards.
+///
+/// The limits can be combined with the other wrappers, like [`LastWins`](crate::LastWins), in any order.
+/// Keys and values are always counted, but fields are only counted when they are inserted into [`Limited`].
+/// Put [`Limited`] on the outside to make sure that duplicate fields collected by [`CollectAll`](crate::CollectAll) are counted too.
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{Limited, PreserveIgnoredFields};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+/// }
+///
+/// type Extra = Limited<serde_json::Map<String, serde_json::Value>, 2, 4, 1024>;
+///
+/// let_assert!(Ok(thing) = serde_json::from_str::<PreserveIgnoredFields<Thing, Extra>>(r#"{"name": "Zohan", "a": 1, "b": 2}"#));
+/// assert!(thing.ignored_fields.inner().len() == 2);
+///
+/// let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFields<Thing, Extra>>(r#"{"name": "Zohan", "a": 1, "b": 2, "c": 3}"#));
+/// assert!(e.to_string().starts_with("too many ignored fields, the maximum is 2"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Limited<U, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> {
+	/// The wrapped ignored fields.
+	inner: U,
+
+	/// The number of ignored fields inserted so far, including those of previous nesting levels.
+	fields: usize,
+
+	/// The total size of the deserialized keys and values so far, including those of previous nesting levels.
+	size: usize,
+}
+
+impl<U, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize>
+	Limited<U, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>
+{
+	/// Wrap a collection of ignored fields.
+	///
+	/// The limits only apply to fields that are added through the wrapper.
+	pub fn new(inner: U) -> Self {
+		Self {
+			inner,
+			fields: 0,
+			size: 0,
+		}
+	}
+
+	/// Get a reference to the wrapped ignored fields.
+	pub fn inner(&self) -> &U {
+		&self.inner
+	}
+
+	/// Consume the wrapper and get the wrapped ignored fields.
+	pub fn into_inner(self) -> U {
+		self.inner
+	}
+
+	/// Run a deserialization function with a size and depth limited deserializer.
+	fn limit<'de, D, F, T>(&mut self, deserializer: D, deserialize: F) -> Result<T, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+		F: FnOnce(&mut U, Limit<D>) -> Result<T, D::Error>,
+	{
+		let mut budget = Budget {
+			size: self.size,
+			max_size: MAX_SIZE,
+			max_depth: MAX_DEPTH,
+		};
+		let result = deserialize(&mut self.inner, Limit::new(deserializer, &mut budget, 0));
+		self.size = budget.size;
+		result
+	}
+}
+
+impl<U: PartialEq, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> PartialEq
+	for Limited<U, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>
+{
+	fn eq(&self, other: &Self) -> bool {
+		self.inner == other.inner
+	}
+}
+
+impl<'de, U, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> DeserializeIgnoredFields<'de>
+	for Limited<U, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>
+where
+	U: DeserializeIgnoredFields<'de>,
+{
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_deserialize_ignored_fields!(inner, except insert, deserialize_key, deserialize_value, continue_from);
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		if self.fields >= MAX_FIELDS {
+			return Err(E::custom(too_many_fields(MAX_FIELDS)));
+		}
+		self.inner.insert(key, value)?;
+		self.fields += 1;
+		Ok(())
+	}
+
+	fn deserialize_key<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Key, D::Error> {
+		self.limit(deserializer, |inner, deserializer| inner.deserialize_key(deserializer))
+	}
+
+	fn deserialize_value<D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.limit(deserializer, |inner, deserializer| {
+			inner.deserialize_value(deserializer)
+		})
+	}
+
+	fn continue_from(&mut self, previous: &Self) {
+		self.fields = previous.fields;
+		self.size = previous.size;
+		self.inner.continue_from(&previous.inner)
+	}
+}
+
+impl<U, const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> SerializeIgnoredFields
+	for Limited<U, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>
+where
+	U: SerializeIgnoredFields,
+{
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_serialize_ignored_fields!(inner);
+}
+
+/// Format the error message for exceeding the maximum number of ignored fields.
+fn too_many_fields(max: usize) -> String {
+	format!("too many ignored fields, the maximum is {max}")
+}
+
+/// The remaining size and depth for deserializing an ignored field.
+struct Budget {
+	/// The total size used so far.
+	size: usize,
+
+	/// The maximum total size.
+	max_size: usize,
+
+	/// The maximum nesting depth.
+	max_depth: usize,
+}
+
+impl Budget {
+	/// Add to the total size, or return an error if that exceeds the maximum size.
+	fn add<E: serde::de::Error>(&mut self, size: usize) -> Result<(), E> {
+		self.size = self.size.saturating_add(size);
+		if self.size > self.max_size {
+			Err(E::custom(format_args!(
+				"ignored fields are too large, the maximum size is {} bytes",
+				self.max_size
+			)))
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Enter a nested value, or return an error if that exceeds the maximum depth.
+	fn enter<E: serde::de::Error>(&mut self, depth: usize) -> Result<usize, E> {
+		if depth >= self.max_depth {
+			return Err(E::custom(format_args!(
+				"ignored field is nested too deeply, the maximum depth is {}",
+				self.max_depth
+			)));
+		}
+		self.add(1)?;
+		Ok(depth + 1)
+	}
+}
+
+/// Wrapper for a [`serde::Deserializer`] and everything it hands out to enforce a [`Budget`].
+struct Limit<'a, Inner> {
+	/// The wrapped object.
+	inner: Inner,
+
+	/// The budget to take the size of all values from.
+	budget: &'a mut Budget,
+
+	/// The nesting depth of the wrapped object.
+	depth: usize,
+}
+
+impl<'a, Inner> Limit<'a, Inner> {
+	/// Wrap an object.
+	fn new(inner: Inner, budget: &'a mut Budget, depth: usize) -> Self {
+		Self { inner, budget, depth }
+	}
+}
+
+/// Forward deserialize functions to `self.inner` with a wrapped visitor.
+macro_rules! forward_deserialize {
+	($($ident:ident($($arg:ident: $type:ty),*);)*) => {
+		$(
+			fn $ident<V: serde::de::Visitor<'de>>(self, $($arg: $type,)* visitor: V) -> Result<V::Value, Self::Error> {
+				self.inner.$ident($($arg,)* Limit::new(visitor, self.budget, self.depth))
+			}
+		)*
+	};
+}
+
+impl<'a, 'de, D: serde::Deserializer<'de>> serde::Deserializer<'de> for Limit<'a, D> {
+	type Error = D::Error;
+
+	forward_deserialize! {
+		deserialize_any();
+		deserialize_bool();
+		deserialize_i8();
+		deserialize_i16();
+		deserialize_i32();
+		deserialize_i64();
+		deserialize_i128();
+		deserialize_u8();
+		deserialize_u16();
+		deserialize_u32();
+		deserialize_u64();
+		deserialize_u128();
+		deserialize_f32();
+		deserialize_f64();
+		deserialize_char();
+		deserialize_str();
+		deserialize_string();
+		deserialize_bytes();
+		deserialize_byte_buf();
+		deserialize_option();
+		deserialize_unit();
+		deserialize_unit_struct(name: &'static str);
+		deserialize_newtype_struct(name: &'static str);
+		deserialize_seq();
+		deserialize_tuple(len: usize);
+		deserialize_tuple_struct(name: &'static str, len: usize);
+		deserialize_map();
+		deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+		deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+		deserialize_identifier();
+		deserialize_ignored_any();
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.inner.is_human_readable()
+	}
+}
+
+impl<'a, 'de, Seed: serde::de::DeserializeSeed<'de>> serde::de::DeserializeSeed<'de> for Limit<'a, Seed> {
+	type Value = Seed::Value;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.inner
+			.deserialize(Limit::new(deserializer, self.budget, self.depth))
+	}
+}
+
+/// Forward visitor functions for primitive values to `self.inner` after adding their size to the budget.
+macro_rules! forward_visit {
+	($(($ident:ident, $type:ty, |$value:ident| $size:expr))*) => {
+		$(
+			fn $ident<E: serde::de::Error>(self, value: $type) -> Result<Self::Value, E> {
+				let $value = &value;
+				self.budget.add($size)?;
+				self.inner.$ident(value)
+			}
+		)*
+	};
+}
+
+impl<'a, 'de, V: serde::de::Visitor<'de>> serde::de::Visitor<'de> for Limit<'a, V> {
+	type Value = V::Value;
+
+	forward_visit! {
+		(visit_bool, bool, |x| std::mem::size_of_val(x))
+		(visit_i8, i8, |x| std::mem::size_of_val(x))
+		(visit_i16, i16, |x| std::mem::size_of_val(x))
+		(visit_i32, i32, |x| std::mem::size_of_val(x))
+		(visit_i64, i64, |x| std::mem::size_of_val(x))
+		(visit_i128, i128, |x| std::mem::size_of_val(x))
+		(visit_u8, u8, |x| std::mem::size_of_val(x))
+		(visit_u16, u16, |x| std::mem::size_of_val(x))
+		(visit_u32, u32, |x| std::mem::size_of_val(x))
+		(visit_u64, u64, |x| std::mem::size_of_val(x))
+		(visit_u128, u128, |x| std::mem::size_of_val(x))
+		(visit_f32, f32, |x| std::mem::size_of_val(x))
+		(visit_f64, f64, |x| std::mem::size_of_val(x))
+		(visit_char, char, |x| x.len_utf8())
+		(visit_str, &str, |x| x.len())
+		(visit_borrowed_str, &'de str, |x| x.len())
+		(visit_string, String, |x| x.len())
+		(visit_bytes, &[u8], |x| x.len())
+		(visit_borrowed_bytes, &'de [u8], |x| x.len())
+		(visit_byte_buf, Vec<u8>, |x| x.len())
+	}
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		self.inner.expecting(formatter)
+	}
+
+	fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		self.budget.add(1)?;
+		self.inner.visit_none()
+	}
+
+	fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.inner.visit_some(Limit::new(deserializer, self.budget, self.depth))
+	}
+
+	fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		self.budget.add(1)?;
+		self.inner.visit_unit()
+	}
+
+	fn visit_newtype_struct<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		self.inner
+			.visit_newtype_struct(Limit::new(deserializer, self.budget, self.depth))
+	}
+
+	fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+		let depth = self.budget.enter(self.depth)?;
+		self.inner.visit_seq(Limit::new(seq, self.budget, depth))
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+		let depth = self.budget.enter(self.depth)?;
+		self.inner.visit_map(Limit::new(map, self.budget, depth))
+	}
+
+	fn visit_enum<A: serde::de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+		self.inner.visit_enum(Limit::new(data, self.budget, self.depth))
+	}
+}
+
+impl<'a, 'de, A: serde::de::SeqAccess<'de>> serde::de::SeqAccess<'de> for Limit<'a, A> {
+	type Error = A::Error;
+
+	fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		self.inner.next_element_seed(Limit::new(seed, self.budget, self.depth))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		self.inner.size_hint()
+	}
+}
+
+impl<'a, 'de, A: serde::de::MapAccess<'de>> serde::de::MapAccess<'de> for Limit<'a, A> {
+	type Error = A::Error;
+
+	fn next_key_seed<K: serde::de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		self.inner.next_key_seed(Limit::new(seed, self.budget, self.depth))
+	}
+
+	fn next_value_seed<V: serde::de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		self.inner.next_value_seed(Limit::new(seed, self.budget, self.depth))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		self.inner.size_hint()
+	}
+}
+
+impl<'a, 'de, A: serde::de::EnumAccess<'de>> serde::de::EnumAccess<'de> for Limit<'a, A> {
+	type Error = A::Error;
+	type Variant = Limit<'a, A::Variant>;
+
+	fn variant_seed<V: serde::de::DeserializeSeed<'de>>(
+		self,
+		seed: V,
+	) -> Result<(V::Value, Self::Variant), Self::Error> {
+		let (value, variant) = self
+			.inner
+			.variant_seed(Limit::new(seed, &mut *self.budget, self.depth))?;
+		Ok((value, Limit::new(variant, self.budget, self.depth)))
+	}
+}
+
+impl<'a, 'de, A: serde::de::VariantAccess<'de>> serde::de::VariantAccess<'de> for Limit<'a, A> {
+	type Error = A::Error;
+
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		self.inner.unit_variant()
+	}
+
+	fn newtype_variant_seed<T: serde::de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		self.inner
+			.newtype_variant_seed(Limit::new(seed, self.budget, self.depth))
+	}
+
+	fn tuple_variant<V: serde::de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.inner
+			.tuple_variant(len, Limit::new(visitor, self.budget, self.depth))
+	}
+
+	fn struct_variant<V: serde::de::Visitor<'de>>(
+		self,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		self.inner
+			.struct_variant(fields, Limit::new(visitor, self.budget, self.depth))
+	}
+}
@@ -0,0 +1,120 @@
+use crate::forward::{forward_deserialize_ignored_fields, forward_serialize_ignored_fields};
+use crate::{DeserializeIgnoredFields, PreserveIgnoredFields, SerializeIgnoredFields};
+
+/// Information about the type that the ignored fields were captured from.
+///
+/// This records what `serde` passes to [`serde::Deserializer::deserialize_struct()`]
+/// and [`serde::Deserializer::deserialize_enum()`].
+/// For a struct variant of an enum, the name and variants are those of the enum and the fields are those of the variant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metadata {
+	/// The name of the struct or enum, if it was deserialized as one.
+	pub name: Option<&'static str>,
+
+	/// The names of the known fields of the struct or struct variant.
+	pub fields: &'static [&'static str],
+
+	/// The names of the variants of the enum.
+	pub variants: &'static [&'static str],
+}
+
+impl Metadata {
+	/// Find the known field that an ignored field was most likely meant to be.
+	///
+	/// A known field is only returned if it is similar enough to be a likely typo.
+	/// See [`IgnoredFieldNames::suggestions()`](crate::IgnoredFieldNames::suggestions) for details.
+	pub fn suggest(&self, name: &str) -> Option<&'static str> {
+		crate::suggest::closest_field(name, self.fields)
+	}
+
+	/// Pass the metadata to a collection of ignored fields.
+	pub(crate) fn apply_to<'de, U: DeserializeIgnoredFields<'de>>(&self, ignored_fields: &mut U) {
+		if let Some(name) = self.name {
+			ignored_fields.set_name(name);
+		}
+		if !self.fields.is_empty() {
+			ignored_fields.set_known_fields(self.fields);
+		}
+		if !self.variants.is_empty() {
+			ignored_fields.set_known_variants(self.variants);
+		}
+	}
+}
+
+/// Wrapper for ignored fields that also records the [`Metadata`] of the deserialized type.
+///
+/// It can be used with any type that implements [`DeserializeIgnoredFields`].
+/// It only implements [`SerializeIgnoredFields`] if the wrapped type does, and the metadata is not serialized.
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{PreserveIgnoredFields, WithMetadata};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct ServerConfig {
+///   host: String,
+///   port: u16,
+/// }
+///
+/// let_assert!(Ok(config) = serde_yaml::from_str::<PreserveIgnoredFields<ServerConfig, WithMetadata<serde_yaml::Mapping>>>("
+///   host: localhost
+///   port: 8080
+///   prot: 8081
+/// "));
+/// let metadata = config.metadata();
+/// assert!(metadata.name == Some("ServerConfig"));
+/// assert!(metadata.fields == ["host", "port"]);
+/// assert!(metadata.suggest("prot") == Some("port"));
+/// assert!(config.ignored_fields.fields["prot"] == 8081);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WithMetadata<U> {
+	/// The wrapped ignored fields.
+	pub fields: U,
+
+	/// The metadata of the deserialized type.
+	pub metadata: Metadata,
+}
+
+impl<U> WithMetadata<U> {
+	/// Create a new [`WithMetadata`] struct from the ignored fields and the metadata.
+	pub fn new(fields: U, metadata: Metadata) -> Self {
+		Self { fields, metadata }
+	}
+}
+
+impl<T, U> PreserveIgnoredFields<T, WithMetadata<U>> {
+	/// Get the metadata of the deserialized type.
+	pub fn metadata(&self) -> &Metadata {
+		&self.ignored_fields.metadata
+	}
+}
+
+impl<'de, U: DeserializeIgnoredFields<'de>> DeserializeIgnoredFields<'de> for WithMetadata<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_deserialize_ignored_fields!(fields, except set_name, set_known_fields, set_known_variants);
+
+	fn set_name(&mut self, name: &'static str) {
+		self.metadata.name = Some(name);
+		self.fields.set_name(name)
+	}
+
+	fn set_known_fields(&mut self, fields: &'static [&'static str]) {
+		self.metadata.fields = fields;
+		self.fields.set_known_fields(fields)
+	}
+
+	fn set_known_variants(&mut self, variants: &'static [&'static str]) {
+		self.metadata.variants = variants;
+		self.fields.set_known_variants(variants)
+	}
+}
+
+impl<U: SerializeIgnoredFields> SerializeIgnoredFields for WithMetadata<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_serialize_ignored_fields!(fields);
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use crate::forward::{forward_deserialize_ignored_fields, forward_serialize_ignored_fields};
+use crate::path::key_name;
+use crate::{DeserializeIgnoredFields, SerializeIgnoredFields};
+
+/// The position of an ignored field relative to the other fields of a map or struct.
+///
+/// See [`KeepOrder`] for a wrapper that records the position of ignored fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPosition<'a> {
+	/// The ignored field comes before all other fields.
+	Start,
+
+	/// The ignored field comes after the field with the given name.
+	After(&'a str),
+
+	/// The ignored field comes after all other fields.
+	End,
+}
+
+/// Wrapper for ignored fields that remembers where they appeared between the other fields.
+///
+/// When deserialized, it records which field came before each ignored field.
+/// When serialized, each ignored field is added right after that field again,
+/// so re-serializing a value keeps the fields in their original order.
+/// If that field is not serialized at all, the ignored field is added at the end.
+///
+/// It can be used with any type that implements [`DeserializeIgnoredFields`],
+/// as long as the keys of the ignored fields implement [`serde::Serialize`].
+/// Ignored fields that followed the same field are serialized in the order of the wrapped type,
+/// so use a type that keeps the insertion order, like `Vec<(K, V)>`, to keep their relative order too.
+/// If the same key appears more than once, all occurrences are placed after the field that preceded the first one.
+///
+/// ```
+/// # use assert2::{assert, let_assert};
+/// use serde_ignored_fields::{KeepOrder, PreserveIgnoredFields};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Person {
+///   name: String,
+///   hobby: String,
+/// }
+///
+/// let data = r#"{"name":"Zohan","colour":"red","hobby":"hair dressing"}"#;
+/// let_assert!(Ok(person) = serde_json::from_str::<PreserveIgnoredFields<Person, KeepOrder<serde_json::Map<String, serde_json::Value>>>>(data));
+/// let_assert!(Ok(serialized) = serde_json::to_string(&person));
+/// assert!(serialized == data);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeepOrder<U> {
+	/// The wrapped ignored fields.
+	pub fields: U,
+
+	/// The position of each ignored field, by name.
+	positions: BTreeMap<String, Anchor>,
+
+	/// The position of the next ignored field.
+	next: Anchor,
+}
+
+/// The recorded position of an ignored field.
+#[derive(Debug, Clone, Default, PartialEq)]
+enum Anchor {
+	/// Before all other fields.
+	Start,
+
+	/// After the field with the given name.
+	After(String),
+
+	/// After all other fields.
+	#[default]
+	End,
+}
+
+impl Anchor {
+	/// Get the anchor as a [`FieldPosition`].
+	fn as_position(&self) -> FieldPosition<'_> {
+		match self {
+			Self::Start => FieldPosition::Start,
+			Self::After(name) => FieldPosition::After(name),
+			Self::End => FieldPosition::End,
+		}
+	}
+}
+
+impl<U> KeepOrder<U> {
+	/// Wrap a collection of ignored fields.
+	///
+	/// All fields that are already in the collection are added at the end when serialized.
+	pub fn new(fields: U) -> Self {
+		Self {
+			fields,
+			positions: BTreeMap::new(),
+			next: Anchor::End,
+		}
+	}
+
+	/// Get the position of the ignored field with the given name.
+	///
+	/// Returns [`FieldPosition::End`] if the position of the field is not known.
+	pub fn position_of(&self, name: &str) -> FieldPosition<'_> {
+		self.positions
+			.get(name)
+			.map_or(FieldPosition::End, |anchor| anchor.as_position())
+	}
+}
+
+impl<'de, U> DeserializeIgnoredFields<'de> for KeepOrder<U>
+where
+	U: DeserializeIgnoredFields<'de>,
+	U::Key: serde::Serialize,
+{
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_deserialize_ignored_fields!(fields, except insert, set_position);
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		let anchor = std::mem::take(&mut self.next);
+		if let Some(name) = key_name(&key) {
+			self.positions.entry(name).or_insert(anchor);
+		}
+		self.fields.insert(key, value)
+	}
+
+	fn set_position(&mut self, position: FieldPosition<'_>) {
+		self.next = match position {
+			FieldPosition::Start => Anchor::Start,
+			FieldPosition::After(name) => Anchor::After(name.into()),
+			FieldPosition::End => Anchor::End,
+		};
+		self.fields.set_position(position)
+	}
+}
+
+impl<U: SerializeIgnoredFields> SerializeIgnoredFields for KeepOrder<U> {
+	type Key = U::Key;
+	type Value = U::Value;
+
+	forward_serialize_ignored_fields!(fields, except position);
+
+	fn position(&self, key: &Self::Key) -> FieldPosition<'_> {
+		match key_name(key) {
+			Some(name) => self.position_of(&name),
+			None => FieldPosition::End,
+		}
+	}
+}
@@ -0,0 +1,292 @@
+/// Path to a nested value, used to address the ignored fields of nested structs and maps.
+///
+/// The empty path refers to the top-level value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FieldPath {
+	/// The segments of the path.
+	segments: Vec<PathSegment>,
+}
+
+/// A single segment of a [`FieldPath`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+	/// The value of a field of a struct or map.
+	///
+	/// Integer map keys are stored as their decimal representation.
+	Field(String),
+
+	/// An element of a sequence.
+	Index(usize),
+}
+
+impl FieldPath {
+	/// Create a new, empty path that refers to the top-level value.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Get the segments of the path.
+	pub fn segments(&self) -> &[PathSegment] {
+		&self.segments
+	}
+
+	/// Get the number of segments in the path.
+	pub fn len(&self) -> usize {
+		self.segments.len()
+	}
+
+	/// Check if the path is empty, meaning it refers to the top-level value.
+	pub fn is_empty(&self) -> bool {
+		self.segments.is_empty()
+	}
+
+	/// Add a segment to the end of the path.
+	pub fn push(&mut self, segment: impl Into<PathSegment>) {
+		self.segments.push(segment.into())
+	}
+
+	/// Remove the last segment from the path.
+	pub fn pop(&mut self) -> Option<PathSegment> {
+		self.segments.pop()
+	}
+
+	/// Check if `prefix` is a prefix of this path.
+	///
+	/// Every path starts with itself and with the empty path.
+	pub fn starts_with(&self, prefix: &FieldPath) -> bool {
+		self.segments.starts_with(&prefix.segments)
+	}
+
+	/// Create a new path by appending a segment to this path.
+	pub(crate) fn join(&self, segment: PathSegment) -> Self {
+		let mut path = self.clone();
+		path.segments.push(segment);
+		path
+	}
+}
+
+impl<S: Into<PathSegment>> FromIterator<S> for FieldPath {
+	fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+		Self {
+			segments: iter.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl std::fmt::Display for FieldPath {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (i, segment) in self.segments.iter().enumerate() {
+			match segment {
+				PathSegment::Field(name) if i == 0 => write!(f, "{name}")?,
+				PathSegment::Field(name) => write!(f, ".{name}")?,
+				PathSegment::Index(index) => write!(f, "[{index}]")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+impl From<&str> for PathSegment {
+	fn from(value: &str) -> Self {
+		Self::Field(value.into())
+	}
+}
+
+impl From<String> for PathSegment {
+	fn from(value: String) -> Self {
+		Self::Field(value)
+	}
+}
+
+impl From<usize> for PathSegment {
+	fn from(value: usize) -> Self {
+		Self::Index(value)
+	}
+}
+
+/// Get the path segment for a map key that is being serialized.
+///
+/// Returns [`None`] if the key can not be represented as a path segment.
+pub(crate) fn key_segment<K: ?Sized + serde::Serialize>(key: &K) -> Option<PathSegment> {
+	key.serialize(SegmentSerializer).ok()
+}
+
+/// Get the name of a map key that is being serialized.
+///
+/// Returns [`None`] if the key can not be represented as a string.
+pub(crate) fn key_name<K: ?Sized + serde::Serialize>(key: &K) -> Option<String> {
+	match key_segment(key)? {
+		PathSegment::Field(name) => Some(name),
+		PathSegment::Index(_) => None,
+	}
+}
+
+/// Error for [`SegmentSerializer`].
+///
+/// The serializer only has to report failure, so the error carries no information.
+#[derive(Debug)]
+struct NoSegment;
+
+impl std::fmt::Display for NoSegment {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("key can not be used as path segment")
+	}
+}
+
+impl std::error::Error for NoSegment {}
+
+impl serde::ser::Error for NoSegment {
+	fn custom<T: std::fmt::Display>(_msg: T) -> Self {
+		Self
+	}
+}
+
+/// Serializer that turns a map key into a [`PathSegment`].
+struct SegmentSerializer;
+
+/// Implement serialize functions for integer types by converting them to a decimal string.
+macro_rules! serialize_integer {
+	($($ident:ident: $type:ty),* $(,)?) => {
+		$(
+			fn $ident(self, v: $type) -> Result<Self::Ok, Self::Error> {
+				Ok(PathSegment::Field(v.to_string()))
+			}
+		)*
+	};
+}
+
+impl serde::Serializer for SegmentSerializer {
+	type Error = NoSegment;
+	type Ok = PathSegment;
+	type SerializeMap = serde::ser::Impossible<PathSegment, NoSegment>;
+	type SerializeSeq = serde::ser::Impossible<PathSegment, NoSegment>;
+	type SerializeStruct = serde::ser::Impossible<PathSegment, NoSegment>;
+	type SerializeStructVariant = serde::ser::Impossible<PathSegment, NoSegment>;
+	type SerializeTuple = serde::ser::Impossible<PathSegment, NoSegment>;
+	type SerializeTupleStruct = serde::ser::Impossible<PathSegment, NoSegment>;
+	type SerializeTupleVariant = serde::ser::Impossible<PathSegment, NoSegment>;
+
+	serialize_integer!(
+		serialize_i8: i8,
+		serialize_i16: i16,
+		serialize_i32: i32,
+		serialize_i64: i64,
+		serialize_i128: i128,
+		serialize_u8: u8,
+		serialize_u16: u16,
+		serialize_u32: u32,
+		serialize_u64: u64,
+		serialize_u128: u128,
+	);
+
+	fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		Ok(PathSegment::Field(v.into()))
+	}
+
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		Ok(PathSegment::Field(v.into()))
+	}
+
+	fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_some<T: ?Sized + serde::Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+	) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Err(NoSegment)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Err(NoSegment)
+	}
+}
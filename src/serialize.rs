@@ -1,4 +1,12 @@
-use crate::{PreserveIgnoredFields, SerializeIgnoredFields};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "ron")]
+use crate::features::ron::RonStruct;
+use crate::field_names::field_names;
+use crate::path::key_name;
+#[cfg(feature = "ron")]
+use crate::value::{to_value, Value};
+use crate::{CollisionPolicy, FieldPosition, PreserveIgnoredFields, SerializeIgnoredFields};
 
 impl<T, IgnoredFields> serde::Serialize for PreserveIgnoredFields<T, IgnoredFields>
 where
@@ -6,40 +14,107 @@ where
 	IgnoredFields: SerializeIgnoredFields,
 {
 	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-		self.value.serialize(Serializer::new(serializer, &self.ignored_fields))
+		self.value
+			.serialize(Serializer::new(serializer, &self.ignored_fields, &self.value))
 	}
 }
 
-/// Wraper for a [`serde::Serializer`] or [`serde::ser::SerializeMap`] to inject ignored fields.
-struct Serializer<'a, Inner, IgnoredFields> {
+/// Wraper for a [`serde::Serializer`], [`serde::ser::SerializeMap`] or [`StructFields`] to inject ignored fields.
+pub(crate) struct Serializer<'a, Inner, IgnoredFields: SerializeIgnoredFields, V: ?Sized = ()> {
 	/// The wrapped serializer.
 	inner: Inner,
 
 	/// The ignored fields to add.
 	ignored_fields: &'a IgnoredFields,
+
+	/// The tracker for the fields of a map that the ignored fields are added to.
+	tracker: FieldTracker<'a, IgnoredFields>,
+
+	/// The value being serialized.
+	///
+	/// It is serialized again to find the names of its fields before they are serialized,
+	/// and as the body of a struct variant with ignored fields.
+	/// For a compound serializer, this is the unit value.
+	value: &'a V,
+
+	/// If true, the value is being serialized as the body of a newtype variant, so a struct variant is written as a map.
+	variant_body: bool,
+}
+
+impl<'a, Inner, IgnoredFields: SerializeIgnoredFields, V: ?Sized> Serializer<'a, Inner, IgnoredFields, V> {
+	/// Wrap a serializer to serialize a value.
+	pub(crate) fn new(inner: Inner, ignored_fields: &'a IgnoredFields, value: &'a V) -> Self {
+		Self {
+			inner,
+			ignored_fields,
+			tracker: FieldTracker::new(Some(ignored_fields)),
+			value,
+			variant_body: false,
+		}
+	}
+
+	/// Find the names of the fields of the value if they are needed to handle collisions.
+	fn value_fields(&self) -> Option<BTreeSet<String>>
+	where
+		V: serde::Serialize,
+	{
+		value_fields(self.value, self.ignored_fields)
+	}
+}
+
+impl<'a, M, IgnoredFields> Serializer<'a, M, IgnoredFields>
+where
+	M: serde::ser::SerializeMap,
+	IgnoredFields: SerializeIgnoredFields,
+{
+	/// Wrap a map serializer and add the ignored fields that belong at the start of it.
+	///
+	/// The names of the fields of the value are used to handle collisions in advance, if they are known.
+	fn start_map(
+		inner: M,
+		ignored_fields: &'a IgnoredFields,
+		value_fields: Option<&BTreeSet<String>>,
+	) -> Result<Self, M::Error> {
+		let mut map = Self::new(inner, ignored_fields, &());
+		map.tracker.start(&mut map.inner, value_fields)?;
+		Ok(map)
+	}
 }
 
-impl<'a, Inner, IgnoredFields> Serializer<'a, Inner, IgnoredFields> {
-	/// Wrap a serializer.
-	fn new(inner: Inner, ignored_fields: &'a IgnoredFields) -> Self {
-		Self { inner, ignored_fields }
+impl<'a, St, IgnoredFields> Serializer<'a, St, IgnoredFields>
+where
+	St: SerializeFields + serde::ser::SerializeStruct<Error = <St as SerializeFields>::Error>,
+	IgnoredFields: SerializeIgnoredFields,
+{
+	/// Wrap a struct serializer and add the ignored fields that belong at the start of it.
+	///
+	/// The names of the fields of the value are used to handle collisions in advance, if they are known.
+	fn start_struct(
+		inner: St,
+		ignored_fields: &'a IgnoredFields,
+		value_fields: Option<&BTreeSet<String>>,
+	) -> Result<Self, <St as SerializeFields>::Error> {
+		let mut ser = Self::new(inner, ignored_fields, &());
+		ser.tracker.start(&mut ser.inner, value_fields)?;
+		Ok(ser)
 	}
 }
 
-impl<'a, S, IgnoredFields> serde::Serializer for Serializer<'a, S, IgnoredFields>
+impl<'a, S, IgnoredFields, V> serde::Serializer for Serializer<'a, S, IgnoredFields, V>
 where
 	S: serde::Serializer,
 	IgnoredFields: SerializeIgnoredFields,
+	V: ?Sized + serde::Serialize,
 {
 	type Error = S::Error;
 	type Ok = S::Ok;
 	type SerializeMap = Serializer<'a, S::SerializeMap, IgnoredFields>;
 	type SerializeSeq = serde::ser::Impossible<S::Ok, S::Error>;
-	type SerializeStruct = Serializer<'a, S::SerializeMap, IgnoredFields>;
-	type SerializeStructVariant = Serializer<'a, S::SerializeMap, IgnoredFields>;
+	type SerializeStruct = Struct<'a, S, IgnoredFields>;
+	type SerializeStructVariant = StructVariant<'a, S, IgnoredFields>;
 	type SerializeTuple = serde::ser::Impossible<S::Ok, S::Error>;
 	type SerializeTupleStruct = serde::ser::Impossible<S::Ok, S::Error>;
-	type SerializeTupleVariant = serde::ser::Impossible<S::Ok, S::Error>;
+	type SerializeTupleVariant = S::SerializeTupleVariant;
 
 	fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
 		Err(serde::ser::Error::custom(
@@ -138,59 +213,79 @@ where
 	}
 
 	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-		// TODO: is this a problem?
-		use serde::ser::SerializeMap;
-		let map = self.serialize_map(Some(0))?;
-		map.end()
+		if !self.ignored_fields.is_empty() {
+			return Err(serde::ser::Error::custom(
+				"invalid type `none`: can only re-serialize a map or struct with ignored fields",
+			));
+		}
+		self.inner.serialize_none()
 	}
 
 	fn serialize_some<T: ?Sized + serde::Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
-		// TODO: is this a problem?
-		serde::Serialize::serialize(value, self)
+		let value = WithIgnoredFields {
+			value,
+			ignored_fields: self.ignored_fields,
+		};
+		self.inner.serialize_some(&value)
 	}
 
 	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-		// TODO: is this a problem?
-		use serde::ser::SerializeMap;
-		let map = self.serialize_map(Some(0))?;
-		map.end()
+		if !self.ignored_fields.is_empty() {
+			return Err(serde::ser::Error::custom(
+				"invalid type `unit`: can only re-serialize a map or struct with ignored fields",
+			));
+		}
+		self.inner.serialize_unit()
 	}
 
 	fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
-		use serde::ser::SerializeStruct;
-		let ser = self.serialize_struct(name, 0)?;
-		ser.end()
+		if !self.ignored_fields.is_empty() {
+			return Err(serde::ser::Error::custom(
+				"invalid type `unit struct`: can only re-serialize a map or struct with ignored fields",
+			));
+		}
+		self.inner.serialize_unit_struct(name)
 	}
 
 	fn serialize_unit_variant(
 		self,
-		_name: &'static str,
-		_variant_index: u32,
-		_variant: &'static str,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
 	) -> Result<Self::Ok, Self::Error> {
-		Err(serde::ser::Error::custom(
-			"invalid type `enum`: can only re-serialize a map or struct with ignored fields",
-		))
+		if !self.ignored_fields.is_empty() {
+			return Err(serde::ser::Error::custom(
+				"invalid type `unit variant`: can only re-serialize a map or struct with ignored fields",
+			));
+		}
+		self.inner.serialize_unit_variant(name, variant_index, variant)
 	}
 
 	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		value: &T,
 	) -> Result<Self::Ok, Self::Error> {
-		serde::Serialize::serialize(value, self)
+		let value = WithIgnoredFields {
+			value,
+			ignored_fields: self.ignored_fields,
+		};
+		self.inner.serialize_newtype_struct(name, &value)
 	}
 
 	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
 		self,
-		_name: &'static str,
-		_variant_index: u32,
-		_variant: &'static str,
-		_value: &T,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		value: &T,
 	) -> Result<Self::Ok, Self::Error> {
-		Err(serde::ser::Error::custom(
-			"invalid type `enum`: can only re-serialize a map or struct with ignored fields",
-		))
+		let value = WithIgnoredFields {
+			value,
+			ignored_fields: self.ignored_fields,
+		};
+		self.inner
+			.serialize_newtype_variant(name, variant_index, variant, &value)
 	}
 
 	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
@@ -217,37 +312,95 @@ where
 
 	fn serialize_tuple_variant(
 		self,
-		_name: &'static str,
-		_variant_index: u32,
-		_variant: &'static str,
-		_len: usize,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
 	) -> Result<Self::SerializeTupleVariant, Self::Error> {
-		Err(serde::ser::Error::custom(
-			"invalid type `enum`: can only re-serialize a map or struct with ignored fields",
-		))
+		if !self.ignored_fields.is_empty() {
+			return Err(serde::ser::Error::custom(
+				"invalid type `tuple variant`: can only re-serialize a map or struct with ignored fields",
+			));
+		}
+		self.inner.serialize_tuple_variant(name, variant_index, variant, len)
 	}
 
 	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		let len = len.map(|x| x + self.ignored_fields.len());
-		let map = self.inner.serialize_map(len)?;
-		Ok(Serializer::new(map, self.ignored_fields))
-	}
-
-	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
-		let map = self.inner.serialize_map(Some(len + self.ignored_fields.len()))?;
-		Ok(Serializer::new(map, self.ignored_fields))
+		let fields = self.value_fields();
+		let map = self
+			.inner
+			.serialize_map(map_len(len, self.ignored_fields, fields.as_ref()))?;
+		Serializer::start_map(map, self.ignored_fields, fields.as_ref())
+	}
+
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		if self.ignored_fields.is_empty() {
+			let ser = self.inner.serialize_struct(name, len)?;
+			return Ok(Struct::Plain(Serializer::start_struct(
+				StructFields(ser),
+				self.ignored_fields,
+				None,
+			)?));
+		}
+		let fields = self.value_fields();
+		#[cfg(feature = "ron")]
+		if ron_struct(self.ignored_fields) {
+			let ser = RonStruct::new(self.inner, None);
+			return Ok(Struct::Ron(Serializer::start_struct(
+				ser,
+				self.ignored_fields,
+				fields.as_ref(),
+			)?));
+		}
+		let map = self
+			.inner
+			.serialize_map(map_len(Some(len), self.ignored_fields, fields.as_ref()))?;
+		Ok(Struct::Map(Serializer::start_map(
+			map,
+			self.ignored_fields,
+			fields.as_ref(),
+		)?))
 	}
 
 	fn serialize_struct_variant(
 		self,
-		_name: &'static str,
-		_variant_index: u32,
-		_vartiant_name: &'static str,
-		_len: usize,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
 	) -> Result<Self::SerializeStructVariant, Self::Error> {
-		Err(serde::ser::Error::custom(
-			"invalid type `enum`: can only re-serialize a map or struct with ignored fields",
-		))
+		if self.ignored_fields.is_empty() {
+			let ser = self.inner.serialize_struct_variant(name, variant_index, variant, len)?;
+			return Ok(StructVariant::Variant(ser));
+		}
+		let fields = self.value_fields();
+		#[cfg(feature = "ron")]
+		if ron_struct(self.ignored_fields) {
+			let ser = RonStruct::new(self.inner, Some(variant));
+			return Ok(StructVariant::Ron(Serializer::start_struct(
+				ser,
+				self.ignored_fields,
+				fields.as_ref(),
+			)?));
+		}
+		if self.variant_body {
+			let map = self
+				.inner
+				.serialize_map(map_len(Some(len), self.ignored_fields, fields.as_ref()))?;
+			return Ok(StructVariant::Map(Serializer::start_map(
+				map,
+				self.ignored_fields,
+				fields.as_ref(),
+			)?));
+		}
+		let body = VariantBody {
+			value: self.value,
+			ignored_fields: self.ignored_fields,
+		};
+		let ok = self
+			.inner
+			.serialize_newtype_variant(name, variant_index, variant, &body)?;
+		Ok(StructVariant::Done(ok))
 	}
 }
 
@@ -260,11 +413,14 @@ where
 	type Ok = M::Ok;
 
 	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
-		self.inner.serialize_key(key)
+		if self.tracker.key(key)? {
+			self.inner.serialize_key(key)?;
+		}
+		Ok(())
 	}
 
 	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-		self.inner.serialize_value(value)
+		self.tracker.value(&mut self.inner, value)
 	}
 
 	fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
@@ -272,61 +428,532 @@ where
 		K: ?Sized + serde::Serialize,
 		V: ?Sized + serde::Serialize,
 	{
-		self.inner.serialize_entry(key, value)
+		self.tracker.entry(&mut self.inner, key, value)
 	}
 
 	fn end(mut self) -> Result<Self::Ok, Self::Error> {
-		for (key, value) in self.ignored_fields.iter() {
-			self.inner.serialize_entry(key, value)?
-		}
+		self.tracker.end(&mut self.inner)?;
 		self.inner.end()
 	}
 }
 
-impl<'a, M, IgnoredFields> serde::ser::SerializeStruct for Serializer<'a, M, IgnoredFields>
+/// Serializer for a struct that is serialized as a struct or as a map.
+///
+/// Structs with ignored fields are serialized as a map, since the fields of a struct can only have static names.
+/// Self-describing formats encode both the same way, except RON, which gets a [`RonStruct`] for ignored fields in [`AsRonStruct`](crate::AsRonStruct).
+pub(crate) enum Struct<'a, S: serde::Serializer, IgnoredFields: SerializeIgnoredFields> {
+	/// Serialize as a struct.
+	Plain(Serializer<'a, StructFields<S::SerializeStruct>, IgnoredFields>),
+
+	/// Serialize as a map.
+	Map(Serializer<'a, S::SerializeMap, IgnoredFields>),
+
+	/// Serialize as RON struct.
+	#[cfg(feature = "ron")]
+	Ron(Serializer<'a, RonStruct<S>, IgnoredFields>),
+}
+
+impl<'a, S, IgnoredFields> serde::ser::SerializeStruct for Struct<'a, S, IgnoredFields>
 where
-	M: serde::ser::SerializeMap,
+	S: serde::Serializer,
 	IgnoredFields: SerializeIgnoredFields,
 {
-	type Error = M::Error;
-	type Ok = M::Ok;
+	type Error = S::Error;
+	type Ok = S::Ok;
 
 	fn serialize_field<T: ?Sized + serde::Serialize>(
 		&mut self,
 		key: &'static str,
 		value: &T,
 	) -> Result<(), Self::Error> {
-		self.inner.serialize_entry(key, value)
+		match self {
+			Self::Plain(ser) => ser.tracker.field(&mut ser.inner, key, value),
+			Self::Map(map) => map.tracker.entry(&mut map.inner, key, value),
+			#[cfg(feature = "ron")]
+			Self::Ron(ser) => ser.tracker.field(&mut ser.inner, key, value),
+		}
 	}
 
-	fn end(mut self) -> Result<Self::Ok, Self::Error> {
-		for (key, value) in self.ignored_fields.iter() {
-			self.inner.serialize_entry(key, value)?
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+		match self {
+			Self::Plain(ser) => ser.inner.skip_field(key),
+			Self::Map(_) => Ok(()),
+			#[cfg(feature = "ron")]
+			Self::Ron(_) => Ok(()),
+		}
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		match self {
+			Self::Plain(mut ser) => {
+				ser.tracker.end(&mut ser.inner)?;
+				ser.inner.end()
+			},
+			Self::Map(mut map) => {
+				map.tracker.end(&mut map.inner)?;
+				serde::ser::SerializeMap::end(map.inner)
+			},
+			#[cfg(feature = "ron")]
+			Self::Ron(mut ser) => {
+				ser.tracker.end(&mut ser.inner)?;
+				ser.inner.end()
+			},
 		}
-		self.inner.end()
 	}
 }
 
-impl<'a, M, IgnoredFields> serde::ser::SerializeStructVariant for Serializer<'a, M, IgnoredFields>
+/// A borrowed value to serialize with ignored fields.
+struct WithIgnoredFields<'a, T: ?Sized, IgnoredFields> {
+	/// The value to serialize.
+	value: &'a T,
+
+	/// The ignored fields to add.
+	ignored_fields: &'a IgnoredFields,
+}
+
+impl<'a, T, IgnoredFields> serde::Serialize for WithIgnoredFields<'a, T, IgnoredFields>
 where
-	M: serde::ser::SerializeMap,
+	T: ?Sized + serde::Serialize,
 	IgnoredFields: SerializeIgnoredFields,
 {
-	type Error = M::Error;
-	type Ok = M::Ok;
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.value
+			.serialize(Serializer::new(serializer, self.ignored_fields, self.value))
+	}
+}
+
+/// Serializer for a struct variant.
+///
+/// The fields of a struct variant can only have static names, so ignored fields can not be added to it directly.
+/// Instead, a struct variant with ignored fields is serialized as a newtype variant containing a map.
+/// Self-describing formats encode both the same way, except RON, which gets a [`RonStruct`] for ignored fields in [`AsRonStruct`](crate::AsRonStruct).
+pub(crate) enum StructVariant<'a, S: serde::Serializer, IgnoredFields: SerializeIgnoredFields> {
+	/// Serialize as a struct variant.
+	Variant(S::SerializeStructVariant),
+
+	/// The variant was already serialized as a newtype variant by serializing the value again.
+	///
+	/// The fields passed to this serializer are ignored.
+	Done(S::Ok),
+
+	/// Serialize as a map, as the body of a newtype variant.
+	Map(Serializer<'a, S::SerializeMap, IgnoredFields>),
+
+	/// Serialize as RON struct variant.
+	#[cfg(feature = "ron")]
+	Ron(Serializer<'a, RonStruct<S>, IgnoredFields>),
+}
+
+impl<'a, S, IgnoredFields> serde::ser::SerializeStructVariant for StructVariant<'a, S, IgnoredFields>
+where
+	S: serde::Serializer,
+	IgnoredFields: SerializeIgnoredFields,
+{
+	type Error = S::Error;
+	type Ok = S::Ok;
 
 	fn serialize_field<T: ?Sized + serde::Serialize>(
 		&mut self,
 		key: &'static str,
 		value: &T,
 	) -> Result<(), Self::Error> {
-		self.inner.serialize_entry(key, value)
+		match self {
+			Self::Variant(inner) => inner.serialize_field(key, value),
+			Self::Done(_) => Ok(()),
+			Self::Map(map) => map.tracker.entry(&mut map.inner, key, value),
+			#[cfg(feature = "ron")]
+			Self::Ron(ser) => ser.tracker.field(&mut ser.inner, key, value),
+		}
 	}
 
-	fn end(mut self) -> Result<Self::Ok, Self::Error> {
-		for (key, value) in self.ignored_fields.iter() {
-			self.inner.serialize_entry(key, value)?
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+		match self {
+			Self::Variant(inner) => inner.skip_field(key),
+			Self::Done(_) | Self::Map(_) => Ok(()),
+			#[cfg(feature = "ron")]
+			Self::Ron(_) => Ok(()),
 		}
-		self.inner.end()
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		match self {
+			Self::Variant(inner) => inner.end(),
+			Self::Done(ok) => Ok(ok),
+			Self::Map(mut map) => {
+				map.tracker.end(&mut map.inner)?;
+				serde::ser::SerializeMap::end(map.inner)
+			},
+			#[cfg(feature = "ron")]
+			Self::Ron(mut ser) => {
+				ser.tracker.end(&mut ser.inner)?;
+				serde::ser::SerializeStruct::end(ser.inner)
+			},
+		}
+	}
+}
+
+/// The body of a struct variant with ignored fields, serialized as a map.
+struct VariantBody<'a, V: ?Sized, IgnoredFields> {
+	/// The value that serializes as the struct variant.
+	value: &'a V,
+
+	/// The ignored fields to add.
+	ignored_fields: &'a IgnoredFields,
+}
+
+impl<'a, V, IgnoredFields> serde::Serialize for VariantBody<'a, V, IgnoredFields>
+where
+	V: ?Sized + serde::Serialize,
+	IgnoredFields: SerializeIgnoredFields,
+{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		// Serialize the value again, but write the struct variant as a map this time.
+		// This passes the original field values to the real serializer, instead of buffering them.
+		self.value.serialize(Serializer {
+			variant_body: true,
+			..Serializer::new(serializer, self.ignored_fields, self.value)
+		})
+	}
+}
+
+/// Check if a struct or struct variant with ignored fields can be written as RON struct by [`RonStruct`].
+///
+/// This requires ignored fields that opt in with [`SerializeIgnoredFields::ron_struct()`] and a string key for each of them.
+#[cfg(feature = "ron")]
+pub(crate) fn ron_struct<IgnoredFields: SerializeIgnoredFields>(ignored_fields: &IgnoredFields) -> bool {
+	ignored_fields.ron_struct() && ignored_fields.iter().all(|(key, _)| field_name(key).is_some())
+}
+
+/// Get the name of an ignored field to use as struct field name.
+///
+/// Returns [`None`] if the key is not a string.
+#[cfg(feature = "ron")]
+pub(crate) fn field_name<K: ?Sized + serde::Serialize>(key: &K) -> Option<String> {
+	match to_value(key, true).ok()? {
+		Value::String(name) => Some(name),
+		Value::Char(name) => Some(name.into()),
+		_ => None,
+	}
+}
+
+/// Get the names of the fields of a value if they are needed to handle collisions with the ignored fields.
+///
+/// The names are only needed if there are ignored fields and a collision is not an error.
+pub(crate) fn value_fields<V, IgnoredFields>(value: &V, ignored_fields: &IgnoredFields) -> Option<BTreeSet<String>>
+where
+	V: ?Sized + serde::Serialize,
+	IgnoredFields: SerializeIgnoredFields,
+{
+	if ignored_fields.is_empty() || ignored_fields.collision_policy() == CollisionPolicy::Error {
+		return None;
+	}
+	field_names(value)
+}
+
+/// Get the length of a map after adding ignored fields to it.
+///
+/// If fields can be skipped because of a collision, the names of the fields of the value are needed to know the length.
+pub(crate) fn map_len<IgnoredFields: SerializeIgnoredFields>(
+	len: Option<usize>,
+	ignored_fields: &IgnoredFields,
+	value_fields: Option<&BTreeSet<String>>,
+) -> Option<usize> {
+	if ignored_fields.is_empty() {
+		return len;
+	}
+	let len = len? + ignored_fields.len();
+	let collisions = match ignored_fields.collision_policy() {
+		CollisionPolicy::Error => 0,
+		CollisionPolicy::ValueWins => {
+			let value_fields = value_fields?;
+			ignored_fields
+				.iter()
+				.filter(|(key, _)| key_name(key).is_some_and(|name| value_fields.contains(&name)))
+				.count()
+		},
+		CollisionPolicy::IgnoredWins => {
+			let names: BTreeSet<String> = ignored_fields.iter().filter_map(|(key, _)| key_name(key)).collect();
+			value_fields?.intersection(&names).count()
+		},
+	};
+	Some(len - collisions)
+}
+
+/// Tracker for the fields of a map that ignored fields are added to during serialization.
+///
+/// Ignored fields are added after the field reported by [`SerializeIgnoredFields::position()`].
+/// Fields of the value with the same key as an ignored field are handled according to [`SerializeIgnoredFields::collision_policy()`].
+/// If there are no ignored fields, the fields of the value are not inspected at all.
+pub(crate) struct FieldTracker<'a, IgnoredFields: SerializeIgnoredFields> {
+	/// The ignored fields to add.
+	ignored_fields: Option<&'a IgnoredFields>,
+
+	/// The ignored fields that belong after a field of the value, by the name of that field.
+	after: BTreeMap<&'a str, Vec<Entry<'a, IgnoredFields>>>,
+
+	/// What to do if a field of the value has the same key as an ignored field.
+	policy: CollisionPolicy,
+
+	/// The names of the ignored fields.
+	names: BTreeSet<String>,
+
+	/// The names of the fields serialized so far, or `None` if all ignored fields are added at the end.
+	seen: Option<BTreeSet<String>>,
+
+	/// The names of the ignored fields that are replaced by a field of the value.
+	replaced: BTreeSet<String>,
+
+	/// The name of the last key passed to [`Self::key()`], until the ignored fields after it have been added.
+	pending: Option<String>,
+
+	/// If true, the value of the current entry is skipped because the ignored field with the same key takes precedence.
+	skip_value: bool,
+}
+
+/// A reference to the key and value of an ignored field.
+type Entry<'a, IgnoredFields> = (
+	&'a <IgnoredFields as SerializeIgnoredFields>::Key,
+	&'a <IgnoredFields as SerializeIgnoredFields>::Value,
+);
+
+impl<'a, IgnoredFields: SerializeIgnoredFields> FieldTracker<'a, IgnoredFields> {
+	/// Create a new tracker for the ignored fields of a map, if there are any.
+	pub(crate) fn new(ignored_fields: Option<&'a IgnoredFields>) -> Self {
+		Self {
+			ignored_fields,
+			after: BTreeMap::new(),
+			policy: CollisionPolicy::Error,
+			names: BTreeSet::new(),
+			seen: None,
+			replaced: BTreeSet::new(),
+			pending: None,
+			skip_value: false,
+		}
+	}
+
+	/// Start tracking the fields of a map and add the ignored fields that belong before all other fields.
+	///
+	/// If the names of the fields of the value are known, the ignored fields replaced by a field of the value are skipped in advance.
+	pub(crate) fn start<M: SerializeFields>(
+		&mut self,
+		map: &mut M,
+		value_fields: Option<&BTreeSet<String>>,
+	) -> Result<(), M::Error> {
+		let Some(ignored_fields) = self.ignored_fields else {
+			return Ok(());
+		};
+		ignored_fields.check()?;
+		self.policy = ignored_fields.collision_policy();
+		self.names = ignored_fields.iter().filter_map(|(key, _)| key_name(key)).collect();
+
+		if self.policy == CollisionPolicy::ValueWins {
+			match value_fields {
+				Some(value_fields) => self.replaced = self.names.intersection(value_fields).cloned().collect(),
+				// Without the names of the fields of the value,
+				// an ignored field can only be skipped if it comes after all fields of the value.
+				None => return Ok(()),
+			}
+		}
+		if ignored_fields
+			.iter()
+			.all(|(key, _)| ignored_fields.position(key) == FieldPosition::End)
+		{
+			return Ok(());
+		}
+		self.seen = Some(BTreeSet::new());
+		for (key, value) in ignored_fields.iter() {
+			if self.is_replaced(key) {
+				continue;
+			}
+			match ignored_fields.position(key) {
+				FieldPosition::Start => map.serialize_ignored(key, value)?,
+				FieldPosition::After(name) => self.after.entry(name).or_default().push((key, value)),
+				FieldPosition::End => (),
+			}
+		}
+		Ok(())
+	}
+
+	/// Check the key of a field of the value before it is added to the map.
+	///
+	/// Returns `false` if the field must be skipped because an ignored field with the same key takes precedence.
+	pub(crate) fn key<K, E>(&mut self, key: &K) -> Result<bool, E>
+	where
+		K: ?Sized + serde::Serialize,
+		E: serde::ser::Error,
+	{
+		self.pending = None;
+		self.skip_value = false;
+		if self.names.is_empty() && self.seen.is_none() {
+			return Ok(true);
+		}
+		let Some(name) = key_name(key) else {
+			return Ok(true);
+		};
+		if self.names.contains(&name) {
+			match self.policy {
+				CollisionPolicy::Error => {
+					return Err(E::custom(format_args!(
+						"ignored field `{name}` has the same key as a field of the serialized value"
+					)))
+				},
+				CollisionPolicy::ValueWins => {
+					self.replaced.insert(name.clone());
+				},
+				CollisionPolicy::IgnoredWins => self.skip_value = true,
+			}
+		}
+		self.pending = Some(name);
+		Ok(!self.skip_value)
+	}
+
+	/// Add the value of the entry for the last key passed to [`Self::key()`], followed by the ignored fields that belong after it.
+	pub(crate) fn value<V, M>(&mut self, map: &mut M, value: &V) -> Result<(), M::Error>
+	where
+		V: ?Sized + serde::Serialize,
+		M: serde::ser::SerializeMap,
+	{
+		if !std::mem::take(&mut self.skip_value) {
+			map.serialize_value(value)?;
+		}
+		self.after(map)
+	}
+
+	/// Add a field of the value, followed by the ignored fields that belong after it.
+	pub(crate) fn entry<K, V, M>(&mut self, map: &mut M, key: &K, value: &V) -> Result<(), M::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize,
+		M: serde::ser::SerializeMap,
+	{
+		if self.key(key)? {
+			map.serialize_entry(key, value)?;
+		}
+		self.skip_value = false;
+		self.after(map)
+	}
+
+	/// Add a field of a struct, followed by the ignored fields that belong after it.
+	pub(crate) fn field<V, St>(
+		&mut self,
+		fields: &mut St,
+		key: &'static str,
+		value: &V,
+	) -> Result<(), <St as SerializeFields>::Error>
+	where
+		V: ?Sized + serde::Serialize,
+		St: SerializeFields + serde::ser::SerializeStruct<Error = <St as SerializeFields>::Error>,
+	{
+		if self.key(key)? {
+			fields.serialize_field(key, value)?;
+		}
+		self.skip_value = false;
+		self.after(fields)
+	}
+
+	/// Add the ignored fields that belong after the last key passed to [`Self::key()`].
+	///
+	/// Does nothing if a field with the same name was already serialized.
+	fn after<M: SerializeFields>(&mut self, map: &mut M) -> Result<(), M::Error> {
+		let (Some(seen), Some(name)) = (&mut self.seen, self.pending.take()) else {
+			return Ok(());
+		};
+		if seen.contains(&name) {
+			return Ok(());
+		}
+		for (key, value) in self.after.remove(name.as_str()).unwrap_or_default() {
+			map.serialize_ignored(key, value)?;
+		}
+		seen.insert(name);
+		Ok(())
+	}
+
+	/// Add the remaining ignored fields after all other fields.
+	///
+	/// This includes the ignored fields that belong after a field that was not serialized,
+	/// but not the ignored fields that were replaced by a field of the value.
+	pub(crate) fn end<M: SerializeFields>(self, map: &mut M) -> Result<(), M::Error> {
+		let Some(ignored_fields) = self.ignored_fields else {
+			return Ok(());
+		};
+		for (key, value) in ignored_fields.iter() {
+			let remaining = match (&self.seen, ignored_fields.position(key)) {
+				(None, _) => true,
+				(Some(_), FieldPosition::Start) => false,
+				(Some(seen), FieldPosition::After(name)) => !seen.contains(name),
+				(Some(_), FieldPosition::End) => true,
+			};
+			if remaining && !self.is_replaced(key) {
+				map.serialize_ignored(key, value)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Check if an ignored field is replaced by a field of the value with the same key.
+	fn is_replaced<K: ?Sized + serde::Serialize>(&self, key: &K) -> bool {
+		!self.replaced.is_empty() && key_name(key).is_some_and(|name| self.replaced.contains(&name))
+	}
+}
+
+/// A map or struct that ignored fields can be added to.
+pub(crate) trait SerializeFields {
+	/// The error type of the serializer.
+	type Error: serde::ser::Error;
+
+	/// Add an ignored field.
+	fn serialize_ignored<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize;
+}
+
+impl<M: serde::ser::SerializeMap> SerializeFields for M {
+	type Error = M::Error;
+
+	fn serialize_ignored<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize,
+	{
+		self.serialize_entry(key, value)
+	}
+}
+
+/// Wrapper for a [`serde::ser::SerializeStruct`] without ignored fields.
+///
+/// The fields of a struct can only have static names, so adding an ignored field is an error.
+pub(crate) struct StructFields<St>(pub(crate) St);
+
+impl<St: serde::ser::SerializeStruct> SerializeFields for StructFields<St> {
+	type Error = St::Error;
+
+	fn serialize_ignored<K, V>(&mut self, _key: &K, _value: &V) -> Result<(), Self::Error>
+	where
+		K: ?Sized + serde::Serialize,
+		V: ?Sized + serde::Serialize,
+	{
+		Err(serde::ser::Error::custom("ignored fields can not be added to a struct"))
+	}
+}
+
+impl<St: serde::ser::SerializeStruct> serde::ser::SerializeStruct for StructFields<St> {
+	type Error = St::Error;
+	type Ok = St::Ok;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.0.serialize_field(key, value)
+	}
+
+	fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+		self.0.skip_field(key)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.0.end()
 	}
 }
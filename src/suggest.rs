@@ -0,0 +1,42 @@
+/// Find the known field that is most similar to the name of an ignored field.
+///
+/// Returns `None` if none of the known fields is similar enough to be a likely typo.
+/// If multiple fields are equally similar, the first one is returned.
+pub(crate) fn closest_field(name: &str, known_fields: &[&'static str]) -> Option<&'static str> {
+	let max_distance = (name.chars().count() / 3).max(1);
+	known_fields
+		.iter()
+		.map(|field| (edit_distance(name, field), *field))
+		.filter(|(distance, _)| *distance <= max_distance)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, field)| field)
+}
+
+/// Compute the edit distance between two strings.
+///
+/// This counts the number of inserted, removed and substituted characters,
+/// and the number of transpositions of two adjacent characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+
+	// Three rows of the distance matrix: two rows back, the previous row and the current row.
+	let mut before: Vec<usize> = vec![0; b.len() + 1];
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+	let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+	for i in 1..=a.len() {
+		current[0] = i;
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				current[j] = current[j].min(before[j - 2] + 1);
+			}
+		}
+		std::mem::swap(&mut before, &mut previous);
+		std::mem::swap(&mut previous, &mut current);
+	}
+
+	previous[b.len()]
+}
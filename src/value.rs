@@ -0,0 +1,1404 @@
+use std::collections::hash_map::{HashMap, RandomState};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Owned, format independent representation of any value.
+///
+/// This can be used to capture ignored fields from any self-describing format,
+/// and to serialize them again with any other format.
+/// It keeps the exact type of scalars, so integers of all widths, floats, bytes, chars, unit, options and newtypes survive a round trip.
+///
+/// Enums are not supported, since `serde` does not describe the shape of an enum variant to the deserializer.
+/// Formats that represent enums as a map or a string (like JSON) can still be deserialized into a [`Value`].
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>>{
+/// # use assert2::assert;
+/// use serde_ignored_fields::{PreserveIgnoredFields, Value};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Thing {
+///   name: String,
+/// }
+///
+/// let thing: PreserveIgnoredFields<Thing, serde_ignored_fields::Map> = serde_yaml::from_str("
+///   name: Turbo Encabulator
+///   casing: malleable logarithmic
+///   phases: 6
+/// ")?;
+///
+/// assert!(thing.ignored_fields.get("casing") == Some(&Value::from("malleable logarithmic")));
+/// assert!(thing.ignored_fields.get("phases") == Some(&Value::U64(6)));
+///
+/// let json = serde_json::to_string(&thing)?;
+/// assert!(json == r#"{"name":"Turbo Encabulator","casing":"malleable logarithmic","phases":6}"#);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	/// A boolean.
+	Bool(bool),
+
+	/// An 8 bit signed integer.
+	I8(i8),
+
+	/// A 16 bit signed integer.
+	I16(i16),
+
+	/// A 32 bit signed integer.
+	I32(i32),
+
+	/// A 64 bit signed integer.
+	I64(i64),
+
+	/// A 128 bit signed integer.
+	I128(i128),
+
+	/// An 8 bit unsigned integer.
+	U8(u8),
+
+	/// A 16 bit unsigned integer.
+	U16(u16),
+
+	/// A 32 bit unsigned integer.
+	U32(u32),
+
+	/// A 64 bit unsigned integer.
+	U64(u64),
+
+	/// A 128 bit unsigned integer.
+	U128(u128),
+
+	/// A 32 bit floating point number.
+	F32(f32),
+
+	/// A 64 bit floating point number.
+	F64(f64),
+
+	/// A single character.
+	Char(char),
+
+	/// A string.
+	String(String),
+
+	/// A byte array.
+	Bytes(Vec<u8>),
+
+	/// An absent optional value.
+	None,
+
+	/// A present optional value.
+	Some(Box<Value>),
+
+	/// The unit value.
+	Unit,
+
+	/// A newtype struct.
+	///
+	/// The name of the newtype struct is not kept, since deserializers do not report it.
+	/// It is serialized as a newtype struct with an empty name.
+	Newtype(Box<Value>),
+
+	/// A sequence of values.
+	Seq(Vec<Value>),
+
+	/// A map of key/value pairs.
+	Map(Map),
+}
+
+/// Map of [`Value`] keys to [`Value`] values that keeps the insertion order.
+///
+/// The map is backed by a vector with a hash index for [`Map::insert()`] and for collecting ignored fields.
+/// Lookups with [`Map::get()`] and friends compare each key in turn, so they take linear time.
+/// That is fine for the typical amount of ignored fields.
+///
+/// When used to collect ignored fields, duplicate fields produce an error.
+/// Both then and in all other methods, integer keys are considered the same if they have the same value,
+/// even if they have a different type.
+#[derive(Clone, Default)]
+pub struct Map {
+	/// The entries of the map.
+	entries: Vec<(Value, Value)>,
+
+	/// Index from the hash of a key to the position of the first entry with a key with that hash.
+	///
+	/// Only the first `indexed` entries are in the index, the others are added by [`Map::find()`] when needed.
+	index: HashMap<u64, usize>,
+
+	/// The number of entries in the index.
+	indexed: usize,
+
+	/// The hasher for the keys.
+	///
+	/// It is randomly seeded, so that untrusted input can not easily produce keys with the same hash.
+	hasher: RandomState,
+}
+
+impl Value {
+	/// Get the value as a string slice, if it is a [`Value::String`].
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Value::String(x) => Some(x),
+			_ => None,
+		}
+	}
+
+	/// Get the value as a boolean, if it is a [`Value::Bool`].
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Value::Bool(x) => Some(*x),
+			_ => None,
+		}
+	}
+
+	/// Get the value as a sequence, if it is a [`Value::Seq`].
+	pub fn as_seq(&self) -> Option<&[Value]> {
+		match self {
+			Value::Seq(x) => Some(x),
+			_ => None,
+		}
+	}
+
+	/// Get the value as a map, if it is a [`Value::Map`].
+	pub fn as_map(&self) -> Option<&Map> {
+		match self {
+			Value::Map(x) => Some(x),
+			_ => None,
+		}
+	}
+
+	/// Check if two map keys refer to the same field.
+	///
+	/// Integers are compared by value regardless of their type,
+	/// since formats may choose a different integer type than the one that was serialized.
+	pub(crate) fn same_key(&self, other: &Self) -> bool {
+		match (self.as_integer(), other.as_integer()) {
+			(Some(a), Some(b)) => a == b,
+			_ => self.strip() == other.strip(),
+		}
+	}
+
+	/// Hash a map key, such that keys that are the same according to [`Self::same_key()`] have the same hash.
+	pub(crate) fn hash_key<H: Hasher>(&self, state: &mut H) {
+		match self.as_integer() {
+			Some(integer) => integer.hash(state),
+			None => self.strip().hash_value(state),
+		}
+	}
+
+	/// Hash a value, such that values that compare equal have the same hash.
+	fn hash_value<H: Hasher>(&self, state: &mut H) {
+		std::mem::discriminant(self).hash(state);
+		match self {
+			Value::Bool(x) => x.hash(state),
+			Value::I8(x) => x.hash(state),
+			Value::I16(x) => x.hash(state),
+			Value::I32(x) => x.hash(state),
+			Value::I64(x) => x.hash(state),
+			Value::I128(x) => x.hash(state),
+			Value::U8(x) => x.hash(state),
+			Value::U16(x) => x.hash(state),
+			Value::U32(x) => x.hash(state),
+			Value::U64(x) => x.hash(state),
+			Value::U128(x) => x.hash(state),
+			// Positive and negative zero compare equal, so they must have the same hash.
+			Value::F32(x) => (if *x == 0.0 { 0 } else { x.to_bits() }).hash(state),
+			Value::F64(x) => (if *x == 0.0 { 0 } else { x.to_bits() }).hash(state),
+			Value::Char(x) => x.hash(state),
+			Value::String(x) => x.hash(state),
+			Value::Bytes(x) => x.hash(state),
+			Value::None | Value::Unit => (),
+			Value::Some(x) | Value::Newtype(x) => x.hash_value(state),
+			Value::Seq(elements) => {
+				elements.len().hash(state);
+				for element in elements {
+					element.hash_value(state);
+				}
+			},
+			Value::Map(map) => {
+				map.len().hash(state);
+				for (key, value) in map.iter() {
+					key.hash_value(state);
+					value.hash_value(state);
+				}
+			},
+		}
+	}
+
+	/// Get the value of an integer as `(negative, magnitude)`.
+	pub(crate) fn as_integer(&self) -> Option<(bool, u128)> {
+		let signed = |x: i128| Some((x < 0, x.unsigned_abs()));
+		match *self {
+			Value::I8(x) => signed(x.into()),
+			Value::I16(x) => signed(x.into()),
+			Value::I32(x) => signed(x.into()),
+			Value::I64(x) => signed(x.into()),
+			Value::I128(x) => signed(x),
+			Value::U8(x) => Some((false, x.into())),
+			Value::U16(x) => Some((false, x.into())),
+			Value::U32(x) => Some((false, x.into())),
+			Value::U64(x) => Some((false, x.into())),
+			Value::U128(x) => Some((false, x)),
+			Value::Some(ref x) | Value::Newtype(ref x) => x.as_integer(),
+			_ => None,
+		}
+	}
+
+	/// Strip [`Value::Some`] and [`Value::Newtype`] wrappers.
+	pub(crate) fn strip(&self) -> &Self {
+		match self {
+			Value::Some(x) | Value::Newtype(x) => x.strip(),
+			x => x,
+		}
+	}
+
+	/// Strip [`Value::Some`] and [`Value::Newtype`] wrappers from an owned value.
+	pub(crate) fn into_stripped(self) -> Self {
+		match self {
+			Value::Some(x) | Value::Newtype(x) => x.into_stripped(),
+			x => x,
+		}
+	}
+}
+
+/// Implement [`From`] for [`Value`] for types that map directly to a variant.
+macro_rules! impl_from {
+	($(($type:ty, $variant:ident))*) => {
+		$(
+			impl From<$type> for Value {
+				fn from(value: $type) -> Self {
+					Value::$variant(value.into())
+				}
+			}
+		)*
+	};
+}
+
+impl_from!(
+	(bool, Bool)
+	(i8, I8)
+	(i16, I16)
+	(i32, I32)
+	(i64, I64)
+	(i128, I128)
+	(u8, U8)
+	(u16, U16)
+	(u32, U32)
+	(u64, U64)
+	(u128, U128)
+	(f32, F32)
+	(f64, F64)
+	(char, Char)
+	(String, String)
+	(&str, String)
+	(Vec<Value>, Seq)
+	(Map, Map)
+);
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+	fn from(value: Option<T>) -> Self {
+		match value {
+			None => Value::None,
+			Some(x) => Value::Some(Box::new(x.into())),
+		}
+	}
+}
+
+impl PartialEq<str> for Value {
+	fn eq(&self, other: &str) -> bool {
+		self.as_str() == Some(other)
+	}
+}
+
+impl PartialEq<&str> for Value {
+	fn eq(&self, other: &&str) -> bool {
+		self.as_str() == Some(*other)
+	}
+}
+
+impl PartialEq<String> for Value {
+	fn eq(&self, other: &String) -> bool {
+		self.as_str() == Some(other.as_str())
+	}
+}
+
+impl Map {
+	/// Create a new, empty map.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a map from a list of entries, keeping duplicate keys.
+	pub(crate) fn from_entries(entries: Vec<(Value, Value)>) -> Self {
+		Self {
+			entries,
+			..Self::default()
+		}
+	}
+
+	/// Get the number of entries in the map.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Check if the map is empty.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Get the value for a key.
+	pub fn get<Q: ?Sized + MapKey>(&self, key: &Q) -> Option<&Value> {
+		self.entries.iter().find(|(k, _)| key.matches_key(k)).map(|(_, v)| v)
+	}
+
+	/// Get a mutable reference to the value for a key.
+	pub fn get_mut<Q: ?Sized + MapKey>(&mut self, key: &Q) -> Option<&mut Value> {
+		self.entries
+			.iter_mut()
+			.find(|(k, _)| key.matches_key(k))
+			.map(|(_, v)| v)
+	}
+
+	/// Check if the map contains a key.
+	pub fn contains_key<Q: ?Sized + MapKey>(&self, key: &Q) -> bool {
+		self.get(key).is_some()
+	}
+
+	/// Insert a key/value pair into the map.
+	///
+	/// If the key was already present, the value is replaced in place and the old value is returned.
+	/// Otherwise, the new entry is added at the end.
+	pub fn insert(&mut self, key: impl Into<Value>, value: impl Into<Value>) -> Option<Value> {
+		let key = key.into();
+		let value = value.into();
+		match self.find(&key) {
+			Some(index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+			None => {
+				self.entries.push((key, value));
+				None
+			},
+		}
+	}
+
+	/// Remove a key from the map, returning the value if it was present.
+	///
+	/// The order of the remaining entries is preserved.
+	pub fn remove<Q: ?Sized + MapKey>(&mut self, key: &Q) -> Option<Value> {
+		let index = self.entries.iter().position(|(k, _)| key.matches_key(k))?;
+		// The positions of the following entries change, so they have to be indexed again.
+		self.index.retain(|_, position| *position < index);
+		self.indexed = self.indexed.min(index);
+		Some(self.entries.remove(index).1)
+	}
+
+	/// Iterate over the entries of the map in insertion order.
+	pub fn iter(&self) -> impl Iterator<Item = (&Value, &Value)> {
+		self.entries.iter().map(|(key, value)| (key, value))
+	}
+
+	/// Iterate over the keys of the map in insertion order.
+	pub fn keys(&self) -> impl Iterator<Item = &Value> {
+		self.entries.iter().map(|(key, _)| key)
+	}
+
+	/// Iterate over the values of the map in insertion order.
+	pub fn values(&self) -> impl Iterator<Item = &Value> {
+		self.entries.iter().map(|(_, value)| value)
+	}
+
+	/// Find the position of the entry with the same key according to [`Value::same_key()`].
+	///
+	/// If there are duplicate keys, the position of the first one is returned.
+	pub(crate) fn find(&mut self, key: &Value) -> Option<usize> {
+		while self.indexed < self.entries.len() {
+			let hash = self.hash(&self.entries[self.indexed].0);
+			self.index.entry(hash).or_insert(self.indexed);
+			self.indexed += 1;
+		}
+
+		let &index = self.index.get(&self.hash(key))?;
+		if self.entries[index].0.same_key(key) {
+			Some(index)
+		} else {
+			// Different keys with the same hash are very rare, so just search all entries.
+			self.entries.iter().position(|(k, _)| k.same_key(key))
+		}
+	}
+
+	/// Compute the hash of a key for the index.
+	fn hash(&self, key: &Value) -> u64 {
+		let mut state = self.hasher.build_hasher();
+		key.hash_key(&mut state);
+		state.finish()
+	}
+}
+
+/// A type that can be used to look up an entry in a [`Map`].
+///
+/// A [`Value`] matches keys according to the same rules as [`Map::insert()`],
+/// so integer keys match if they have the same value, even if they have a different type.
+/// Strings match string keys.
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait MapKey: private::Sealed {
+	/// Check if a key of the map matches this key.
+	#[doc(hidden)]
+	fn matches_key(&self, key: &Value) -> bool;
+}
+
+impl MapKey for Value {
+	fn matches_key(&self, key: &Value) -> bool {
+		self.same_key(key)
+	}
+}
+
+impl MapKey for str {
+	fn matches_key(&self, key: &Value) -> bool {
+		key.strip().as_str() == Some(self)
+	}
+}
+
+impl MapKey for String {
+	fn matches_key(&self, key: &Value) -> bool {
+		MapKey::matches_key(self.as_str(), key)
+	}
+}
+
+impl<T: ?Sized + MapKey> MapKey for &T {
+	fn matches_key(&self, key: &Value) -> bool {
+		(**self).matches_key(key)
+	}
+}
+
+/// Private module for the sealed [`MapKey`] trait.
+mod private {
+	/// Supertrait to prevent implementations of [`MapKey`](super::MapKey) outside of this crate.
+	pub trait Sealed {}
+
+	impl Sealed for super::Value {}
+	impl Sealed for str {}
+	impl Sealed for String {}
+	impl<T: ?Sized + Sealed> Sealed for &T {}
+}
+
+impl std::fmt::Debug for Map {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Map").field("entries", &self.entries).finish()
+	}
+}
+
+impl PartialEq for Map {
+	fn eq(&self, other: &Self) -> bool {
+		self.entries == other.entries
+	}
+}
+
+impl<K: Into<Value>, V: Into<Value>> FromIterator<(K, V)> for Map {
+	fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+		let mut map = Self::new();
+		map.extend(iter);
+		map
+	}
+}
+
+impl<K: Into<Value>, V: Into<Value>> Extend<(K, V)> for Map {
+	fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+		for (key, value) in iter {
+			self.insert(key, value);
+		}
+	}
+}
+
+impl IntoIterator for Map {
+	type IntoIter = std::vec::IntoIter<(Value, Value)>;
+	type Item = (Value, Value);
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.entries.into_iter()
+	}
+}
+
+impl<'de> crate::DeserializeIgnoredFields<'de> for Map {
+	type Key = Value;
+	type Value = Value;
+
+	fn insert<E: serde::de::Error>(&mut self, key: Self::Key, value: Self::Value) -> Result<(), E> {
+		if self.find(&key).is_some() {
+			// Show string keys without the `Value::String` around them.
+			let name: &dyn std::fmt::Debug = match &key {
+				Value::String(name) => name,
+				key => key,
+			};
+			return Err(E::custom(format!("duplicate field: {name:?}")));
+		}
+		self.entries.push((key, value));
+		Ok(())
+	}
+
+	fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+		let index = self.find(key)?;
+		Some(&mut self.entries[index].1)
+	}
+
+	fn supports_get_mut(&self) -> bool {
+		true
+	}
+}
+
+impl crate::SerializeIgnoredFields for Map {
+	type Key = Value;
+	type Value = Value;
+
+	fn len(&self) -> usize {
+		Map::len(self)
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+		Map::iter(self)
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for Map {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		match Value::deserialize(deserializer)? {
+			Value::Map(map) => Ok(map),
+			_ => Err(serde::de::Error::custom("invalid type: expected a map")),
+		}
+	}
+}
+
+impl serde::Serialize for Map {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeMap;
+		let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+		for (key, value) in &self.entries {
+			map.serialize_entry(key, value)?;
+		}
+		map.end()
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for Value {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ValueVisitor)
+	}
+}
+
+/// Visitor to deserialize a [`Value`].
+struct ValueVisitor;
+
+/// Implement visitor functions that wrap the value in a [`Value`] variant.
+macro_rules! visit_value {
+	($(($ident:ident, $type:ty, $variant:ident))*) => {
+		$(
+			fn $ident<E: serde::de::Error>(self, value: $type) -> Result<Self::Value, E> {
+				Ok(Value::$variant(value.into()))
+			}
+		)*
+	};
+}
+
+impl<'de> serde::de::Visitor<'de> for ValueVisitor {
+	type Value = Value;
+
+	visit_value!(
+		(visit_bool, bool, Bool)
+		(visit_i8, i8, I8)
+		(visit_i16, i16, I16)
+		(visit_i32, i32, I32)
+		(visit_i64, i64, I64)
+		(visit_i128, i128, I128)
+		(visit_u8, u8, U8)
+		(visit_u16, u16, U16)
+		(visit_u32, u32, U32)
+		(visit_u64, u64, U64)
+		(visit_u128, u128, U128)
+		(visit_f32, f32, F32)
+		(visit_f64, f64, F64)
+		(visit_char, char, Char)
+		(visit_str, &str, String)
+		(visit_string, String, String)
+		(visit_bytes, &[u8], Bytes)
+		(visit_byte_buf, Vec<u8>, Bytes)
+	);
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		formatter.write_str("any value")
+	}
+
+	fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		Ok(Value::None)
+	}
+
+	fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		let value = serde::Deserialize::deserialize(deserializer)?;
+		Ok(Value::Some(Box::new(value)))
+	}
+
+	fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		Ok(Value::Unit)
+	}
+
+	fn visit_newtype_struct<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		let value = serde::Deserialize::deserialize(deserializer)?;
+		Ok(Value::Newtype(Box::new(value)))
+	}
+
+	fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+		while let Some(element) = seq.next_element()? {
+			elements.push(element);
+		}
+		Ok(Value::Seq(elements))
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		// Keep duplicate keys, so that we can detect them when checking which fields were used.
+		let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+		while let Some(entry) = map.next_entry()? {
+			entries.push(entry);
+		}
+		Ok(Value::Map(Map::from_entries(entries)))
+	}
+}
+
+impl serde::Serialize for Value {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeSeq;
+		match self {
+			Value::Bool(x) => serializer.serialize_bool(*x),
+			Value::I8(x) => serializer.serialize_i8(*x),
+			Value::I16(x) => serializer.serialize_i16(*x),
+			Value::I32(x) => serializer.serialize_i32(*x),
+			Value::I64(x) => serializer.serialize_i64(*x),
+			Value::I128(x) => serializer.serialize_i128(*x),
+			Value::U8(x) => serializer.serialize_u8(*x),
+			Value::U16(x) => serializer.serialize_u16(*x),
+			Value::U32(x) => serializer.serialize_u32(*x),
+			Value::U64(x) => serializer.serialize_u64(*x),
+			Value::U128(x) => serializer.serialize_u128(*x),
+			Value::F32(x) => serializer.serialize_f32(*x),
+			Value::F64(x) => serializer.serialize_f64(*x),
+			Value::Char(x) => serializer.serialize_char(*x),
+			Value::String(x) => serializer.serialize_str(x),
+			Value::Bytes(x) => serializer.serialize_bytes(x),
+			Value::None => serializer.serialize_none(),
+			Value::Some(x) => serializer.serialize_some(x),
+			Value::Unit => serializer.serialize_unit(),
+			Value::Newtype(x) => serializer.serialize_newtype_struct("", x),
+			Value::Seq(elements) => {
+				let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+				for element in elements {
+					seq.serialize_element(element)?;
+				}
+				seq.end()
+			},
+			Value::Map(map) => map.serialize(serializer),
+		}
+	}
+}
+
+/// Deserializer that consumes a [`Value`].
+pub(crate) struct ValueDeserializer<E> {
+	/// The content to deserialize.
+	content: Value,
+
+	/// Value to return from [`serde::Deserializer::is_human_readable()`].
+	human_readable: bool,
+
+	/// The error type of the deserializer.
+	_error: PhantomData<fn() -> E>,
+}
+
+impl<E> ValueDeserializer<E> {
+	/// Create a deserializer for the given content.
+	///
+	/// The `human_readable` flag should match the deserializer the content was deserialized from.
+	pub fn new(content: Value, human_readable: bool) -> Self {
+		Self {
+			content,
+			human_readable,
+			_error: PhantomData,
+		}
+	}
+}
+
+impl<'de, E: serde::de::Error> serde::de::IntoDeserializer<'de, E> for ValueDeserializer<E> {
+	type Deserializer = Self;
+
+	fn into_deserializer(self) -> Self::Deserializer {
+		self
+	}
+}
+
+impl<'de, E: serde::de::Error> serde::Deserializer<'de> for ValueDeserializer<E> {
+	type Error = E;
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit_struct seq tuple
+		tuple_struct map struct identifier
+	}
+
+	fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let human_readable = self.human_readable;
+		match self.content {
+			Value::Bool(x) => visitor.visit_bool(x),
+			Value::I8(x) => visitor.visit_i8(x),
+			Value::I16(x) => visitor.visit_i16(x),
+			Value::I32(x) => visitor.visit_i32(x),
+			Value::I64(x) => visitor.visit_i64(x),
+			Value::I128(x) => visitor.visit_i128(x),
+			Value::U8(x) => visitor.visit_u8(x),
+			Value::U16(x) => visitor.visit_u16(x),
+			Value::U32(x) => visitor.visit_u32(x),
+			Value::U64(x) => visitor.visit_u64(x),
+			Value::U128(x) => visitor.visit_u128(x),
+			Value::F32(x) => visitor.visit_f32(x),
+			Value::F64(x) => visitor.visit_f64(x),
+			Value::Char(x) => visitor.visit_char(x),
+			Value::String(x) => visitor.visit_string(x),
+			Value::Bytes(x) => visitor.visit_byte_buf(x),
+			Value::None => visitor.visit_none(),
+			Value::Some(x) => visitor.visit_some(Self::new(*x, human_readable)),
+			Value::Unit => visitor.visit_unit(),
+			Value::Newtype(x) => visitor.visit_newtype_struct(Self::new(*x, human_readable)),
+			Value::Seq(elements) => {
+				let elements = elements.into_iter().map(|x| Self::new(x, human_readable));
+				let mut seq = serde::de::value::SeqDeserializer::new(elements);
+				let value = visitor.visit_seq(&mut seq)?;
+				seq.end()?;
+				Ok(value)
+			},
+			Value::Map(map) => {
+				let entries = map
+					.into_iter()
+					.map(|(key, value)| (Self::new(key, human_readable), Self::new(value, human_readable)));
+				let mut map = serde::de::value::MapDeserializer::new(entries);
+				let value = visitor.visit_map(&mut map)?;
+				map.end()?;
+				Ok(value)
+			},
+		}
+	}
+
+	fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let human_readable = self.human_readable;
+		match self.content {
+			Value::None | Value::Unit => visitor.visit_none(),
+			Value::Some(x) => visitor.visit_some(Self::new(*x, human_readable)),
+			_ => visitor.visit_some(self),
+		}
+	}
+
+	fn deserialize_unit<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Value::None | Value::Unit => visitor.visit_unit(),
+			_ => self.deserialize_any(visitor),
+		}
+	}
+
+	fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let human_readable = self.human_readable;
+		match self.content {
+			Value::Newtype(x) => visitor.visit_newtype_struct(Self::new(*x, human_readable)),
+			_ => visitor.visit_newtype_struct(self),
+		}
+	}
+
+	fn deserialize_enum<V: serde::de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Value::Map(Map { mut entries, .. }) if entries.len() == 1 => {
+				let (variant, value) = entries.remove(0);
+				visitor.visit_enum(EnumDeserializer {
+					variant: Self::new(variant, self.human_readable),
+					value: Some(Self::new(value, self.human_readable)),
+				})
+			},
+			content @ (Value::String(_) | Value::Char(_) | Value::U32(_) | Value::U64(_)) => {
+				visitor.visit_enum(EnumDeserializer {
+					variant: Self::new(content, self.human_readable),
+					value: None,
+				})
+			},
+			_ => Err(E::custom(
+				"invalid type: expected an enum variant name or a map with a single entry",
+			)),
+		}
+	}
+
+	fn deserialize_ignored_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.human_readable
+	}
+}
+
+/// Deserializer that borrows a [`Value`].
+///
+/// This works like [`ValueDeserializer`], but leaves the value intact so it can be used again.
+pub(crate) struct ValueRefDeserializer<'a, E> {
+	/// The content to deserialize.
+	content: &'a Value,
+
+	/// Value to return from [`serde::Deserializer::is_human_readable()`].
+	human_readable: bool,
+
+	/// The error type of the deserializer.
+	_error: PhantomData<fn() -> E>,
+}
+
+impl<'a, E> ValueRefDeserializer<'a, E> {
+	/// Create a deserializer for the given content.
+	///
+	/// The `human_readable` flag should match the deserializer the content was deserialized from.
+	pub fn new(content: &'a Value, human_readable: bool) -> Self {
+		Self {
+			content,
+			human_readable,
+			_error: PhantomData,
+		}
+	}
+}
+
+impl<'a, 'de, E: serde::de::Error> serde::de::IntoDeserializer<'de, E> for ValueRefDeserializer<'a, E> {
+	type Deserializer = Self;
+
+	fn into_deserializer(self) -> Self::Deserializer {
+		self
+	}
+}
+
+impl<'a, 'de, E: serde::de::Error> serde::Deserializer<'de> for ValueRefDeserializer<'a, E> {
+	type Error = E;
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit_struct seq tuple
+		tuple_struct map struct identifier
+	}
+
+	fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let human_readable = self.human_readable;
+		match self.content {
+			Value::Bool(x) => visitor.visit_bool(*x),
+			Value::I8(x) => visitor.visit_i8(*x),
+			Value::I16(x) => visitor.visit_i16(*x),
+			Value::I32(x) => visitor.visit_i32(*x),
+			Value::I64(x) => visitor.visit_i64(*x),
+			Value::I128(x) => visitor.visit_i128(*x),
+			Value::U8(x) => visitor.visit_u8(*x),
+			Value::U16(x) => visitor.visit_u16(*x),
+			Value::U32(x) => visitor.visit_u32(*x),
+			Value::U64(x) => visitor.visit_u64(*x),
+			Value::U128(x) => visitor.visit_u128(*x),
+			Value::F32(x) => visitor.visit_f32(*x),
+			Value::F64(x) => visitor.visit_f64(*x),
+			Value::Char(x) => visitor.visit_char(*x),
+			Value::String(x) => visitor.visit_str(x),
+			Value::Bytes(x) => visitor.visit_bytes(x),
+			Value::None => visitor.visit_none(),
+			Value::Some(x) => visitor.visit_some(Self::new(x, human_readable)),
+			Value::Unit => visitor.visit_unit(),
+			Value::Newtype(x) => visitor.visit_newtype_struct(Self::new(x, human_readable)),
+			Value::Seq(elements) => {
+				let elements = elements.iter().map(|x| Self::new(x, human_readable));
+				let mut seq = serde::de::value::SeqDeserializer::new(elements);
+				let value = visitor.visit_seq(&mut seq)?;
+				seq.end()?;
+				Ok(value)
+			},
+			Value::Map(map) => {
+				let entries = map
+					.iter()
+					.map(|(key, value)| (Self::new(key, human_readable), Self::new(value, human_readable)));
+				let mut map = serde::de::value::MapDeserializer::new(entries);
+				let value = visitor.visit_map(&mut map)?;
+				map.end()?;
+				Ok(value)
+			},
+		}
+	}
+
+	fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Value::None | Value::Unit => visitor.visit_none(),
+			Value::Some(x) => visitor.visit_some(Self::new(x, self.human_readable)),
+			_ => visitor.visit_some(self),
+		}
+	}
+
+	fn deserialize_unit<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Value::None | Value::Unit => visitor.visit_unit(),
+			_ => self.deserialize_any(visitor),
+		}
+	}
+
+	fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Value::Newtype(x) => visitor.visit_newtype_struct(Self::new(x, self.human_readable)),
+			_ => visitor.visit_newtype_struct(self),
+		}
+	}
+
+	fn deserialize_enum<V: serde::de::Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Value::Map(map) if map.len() == 1 => {
+				let (variant, value) = &map.entries[0];
+				visitor.visit_enum(EnumDeserializer {
+					variant: Self::new(variant, self.human_readable),
+					value: Some(Self::new(value, self.human_readable)),
+				})
+			},
+			Value::String(_) | Value::Char(_) | Value::U32(_) | Value::U64(_) => visitor.visit_enum(EnumDeserializer {
+				variant: self,
+				value: None,
+			}),
+			_ => Err(E::custom(
+				"invalid type: expected an enum variant name or a map with a single entry",
+			)),
+		}
+	}
+
+	fn deserialize_ignored_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.human_readable
+	}
+}
+
+/// Deserializer for the variant of an externally tagged enum.
+struct EnumDeserializer<D> {
+	/// The variant identifier.
+	variant: D,
+
+	/// The data of the variant, if any.
+	value: Option<D>,
+}
+
+impl<'de, D: serde::Deserializer<'de>> serde::de::EnumAccess<'de> for EnumDeserializer<D> {
+	type Error = D::Error;
+	type Variant = VariantDeserializer<D>;
+
+	fn variant_seed<V: serde::de::DeserializeSeed<'de>>(
+		self,
+		seed: V,
+	) -> Result<(V::Value, Self::Variant), Self::Error> {
+		let variant = seed.deserialize(self.variant)?;
+		Ok((variant, VariantDeserializer { value: self.value }))
+	}
+}
+
+/// Deserializer for the data of an enum variant.
+struct VariantDeserializer<D> {
+	/// The data of the variant, if any.
+	value: Option<D>,
+}
+
+impl<'de, D: serde::Deserializer<'de>> serde::de::VariantAccess<'de> for VariantDeserializer<D> {
+	type Error = D::Error;
+
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		match self.value {
+			None => Ok(()),
+			Some(value) => serde::Deserialize::deserialize(value),
+		}
+	}
+
+	fn newtype_variant_seed<T: serde::de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		match self.value {
+			Some(value) => seed.deserialize(value),
+			None => Err(serde::de::Error::invalid_type(
+				serde::de::Unexpected::UnitVariant,
+				&"newtype variant",
+			)),
+		}
+	}
+
+	fn tuple_variant<V: serde::de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.value {
+			Some(value) => serde::Deserializer::deserialize_any(value, visitor),
+			None => Err(serde::de::Error::invalid_type(
+				serde::de::Unexpected::UnitVariant,
+				&"tuple variant",
+			)),
+		}
+	}
+
+	fn struct_variant<V: serde::de::Visitor<'de>>(
+		self,
+		_fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		match self.value {
+			Some(value) => serde::Deserializer::deserialize_any(value, visitor),
+			None => Err(serde::de::Error::invalid_type(
+				serde::de::Unexpected::UnitVariant,
+				&"struct variant",
+			)),
+		}
+	}
+}
+
+/// Error that can occur when serializing a value into a [`Value`].
+#[derive(Debug)]
+pub(crate) struct Error {
+	/// The error message.
+	message: String,
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+	fn custom<T: std::fmt::Display>(msg: T) -> Self {
+		Self {
+			message: msg.to_string(),
+		}
+	}
+}
+
+/// Serialize a value into a [`Value`].
+pub(crate) fn to_value<T: ?Sized + serde::Serialize>(value: &T, human_readable: bool) -> Result<Value, Error> {
+	value.serialize(ValueSerializer { human_readable })
+}
+
+/// Serializer that produces a [`Value`].
+#[derive(Copy, Clone)]
+struct ValueSerializer {
+	/// Value to return from [`serde::Serializer::is_human_readable()`].
+	human_readable: bool,
+}
+
+/// Implement serialize functions that wrap the value in a [`Value`] variant.
+macro_rules! serialize_value {
+	($(($ident:ident, $type:ty, $variant:ident))*) => {
+		$(
+			fn $ident(self, v: $type) -> Result<Self::Ok, Self::Error> {
+				Ok(Value::$variant(v.into()))
+			}
+		)*
+	};
+}
+
+impl serde::Serializer for ValueSerializer {
+	type Error = Error;
+	type Ok = Value;
+	type SerializeMap = SerializeMap;
+	type SerializeSeq = SerializeSeq;
+	type SerializeStruct = SerializeMap;
+	type SerializeStructVariant = SerializeMap;
+	type SerializeTuple = SerializeSeq;
+	type SerializeTupleStruct = SerializeSeq;
+	type SerializeTupleVariant = SerializeSeq;
+
+	serialize_value! {
+		(serialize_bool, bool, Bool)
+		(serialize_i8, i8, I8)
+		(serialize_i16, i16, I16)
+		(serialize_i32, i32, I32)
+		(serialize_i64, i64, I64)
+		(serialize_i128, i128, I128)
+		(serialize_u8, u8, U8)
+		(serialize_u16, u16, U16)
+		(serialize_u32, u32, U32)
+		(serialize_u64, u64, U64)
+		(serialize_u128, u128, U128)
+		(serialize_f32, f32, F32)
+		(serialize_f64, f64, F64)
+		(serialize_char, char, Char)
+		(serialize_str, &str, String)
+		(serialize_bytes, &[u8], Bytes)
+	}
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::None)
+	}
+
+	fn serialize_some<T: ?Sized + serde::Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Some(Box::new(value.serialize(self)?)))
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Unit)
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Unit)
+	}
+
+	fn serialize_unit_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::String(variant.into()))
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		Ok(Value::Newtype(Box::new(value.serialize(self)?)))
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		let value = value.serialize(self)?;
+		Ok(Value::Map(Map::from_entries(vec![(
+			Value::String(variant.into()),
+			value,
+		)])))
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Ok(SerializeSeq::new(self, None, len.unwrap_or(0)))
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Ok(SerializeSeq::new(self, None, len))
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Ok(SerializeSeq::new(self, None, len))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(SerializeSeq::new(self, Some(variant), len))
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(SerializeMap::new(self, None, len.unwrap_or(0)))
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(SerializeMap::new(self, None, len))
+	}
+
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(SerializeMap::new(self, Some(variant), len))
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.human_readable
+	}
+}
+
+/// Wrap the content of an enum variant in a single entry map.
+fn wrap_variant(variant: Option<&'static str>, content: Value) -> Value {
+	match variant {
+		None => content,
+		Some(variant) => Value::Map(Map::from_entries(vec![(Value::String(variant.into()), content)])),
+	}
+}
+
+/// Serializer for sequences, tuples and tuple variants.
+struct SerializeSeq {
+	/// The serializer for the elements.
+	serializer: ValueSerializer,
+
+	/// The variant name for tuple variants.
+	variant: Option<&'static str>,
+
+	/// The serialized elements.
+	elements: Vec<Value>,
+}
+
+impl SerializeSeq {
+	/// Create a new sequence serializer.
+	fn new(serializer: ValueSerializer, variant: Option<&'static str>, len: usize) -> Self {
+		Self {
+			serializer,
+			variant,
+			elements: Vec::with_capacity(len.min(4096)),
+		}
+	}
+
+	/// Add an element to the sequence.
+	fn push<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		self.elements.push(value.serialize(self.serializer)?);
+		Ok(())
+	}
+
+	/// Finish the sequence.
+	fn finish(self) -> Result<Value, Error> {
+		Ok(wrap_variant(self.variant, Value::Seq(self.elements)))
+	}
+}
+
+/// Implement a compound serializer trait for [`SerializeSeq`].
+macro_rules! impl_serialize_seq {
+	($($trait:ident::$method:ident),* $(,)?) => {
+		$(
+			impl serde::ser::$trait for SerializeSeq {
+				type Error = Error;
+				type Ok = Value;
+
+				fn $method<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+					self.push(value)
+				}
+
+				fn end(self) -> Result<Self::Ok, Self::Error> {
+					self.finish()
+				}
+			}
+		)*
+	};
+}
+
+impl_serialize_seq!(
+	SerializeSeq::serialize_element,
+	SerializeTuple::serialize_element,
+	SerializeTupleStruct::serialize_field,
+	SerializeTupleVariant::serialize_field,
+);
+
+/// Serializer for maps, structs and struct variants.
+struct SerializeMap {
+	/// The serializer for the keys and values.
+	serializer: ValueSerializer,
+
+	/// The variant name for struct variants.
+	variant: Option<&'static str>,
+
+	/// The serialized entries.
+	entries: Vec<(Value, Value)>,
+
+	/// The last serialized key, waiting for a value.
+	key: Option<Value>,
+}
+
+impl SerializeMap {
+	/// Create a new map serializer.
+	fn new(serializer: ValueSerializer, variant: Option<&'static str>, len: usize) -> Self {
+		Self {
+			serializer,
+			variant,
+			entries: Vec::with_capacity(len.min(4096)),
+			key: None,
+		}
+	}
+
+	/// Add a field to the map.
+	fn field<T: ?Sized + serde::Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+		let value = value.serialize(self.serializer)?;
+		self.entries.push((Value::String(key.into()), value));
+		Ok(())
+	}
+
+	/// Finish the map.
+	fn finish(self) -> Result<Value, Error> {
+		Ok(wrap_variant(self.variant, Value::Map(Map::from_entries(self.entries))))
+	}
+}
+
+impl serde::ser::SerializeMap for SerializeMap {
+	type Error = Error;
+	type Ok = Value;
+
+	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+		self.key = Some(key.serialize(self.serializer)?);
+		Ok(())
+	}
+
+	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+		use serde::ser::Error;
+		let key = self
+			.key
+			.take()
+			.ok_or_else(|| Self::Error::custom("called `serialize_value()` before `serialize_key()`"))?;
+		let value = value.serialize(self.serializer)?;
+		self.entries.push((key, value));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeStruct for SerializeMap {
+	type Error = Error;
+	type Ok = Value;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.field(key, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
+
+impl serde::ser::SerializeStructVariant for SerializeMap {
+	type Error = Error;
+	type Ok = Value;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.field(key, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.finish()
+	}
+}
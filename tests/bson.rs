@@ -0,0 +1,100 @@
+#![cfg(feature = "bson")]
+
+use assert2::{assert, let_assert};
+use bson::{doc, Bson, Document};
+use serde_ignored_fields::{PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Customer {
+	name: String,
+	email: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Order {
+	id: i64,
+	customer: Customer,
+}
+
+fn customer() -> Document {
+	doc! {
+		"name": "Zohan",
+		"email": "zohan@example.com",
+		"loyalty": { "tier": "gold", "points": 1200_i64 },
+		"tags": ["vip", "early-adopter"],
+		"created": bson::DateTime::from_millis(1_700_000_000_000),
+		"external_id": bson::oid::ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap(),
+	}
+}
+
+#[test]
+fn round_trip_document() {
+	let data = customer();
+	let_assert!(Ok(customer) = bson::from_document::<PreserveIgnoredFields<Customer, Document>>(data.clone()));
+	assert!(customer.value == Customer {
+		name: "Zohan".into(),
+		email: "zohan@example.com".into(),
+	});
+	assert!(customer.ignored_fields.len() == 4);
+	assert!(customer.ignored_fields.get("loyalty") == data.get("loyalty"));
+	assert!(matches!(customer.ignored_fields.get("created"), Some(Bson::DateTime(_))));
+	assert!(matches!(customer.ignored_fields.get("external_id"), Some(Bson::ObjectId(_))));
+
+	let_assert!(Ok(serialized) = bson::to_document(&customer));
+	assert!(serialized == data);
+}
+
+#[test]
+fn round_trip_raw_bytes() {
+	let data = customer();
+	let_assert!(Ok(bytes) = bson::to_vec(&data));
+
+	let_assert!(Ok(customer) = bson::from_slice::<PreserveIgnoredFields<Customer, Document>>(&bytes));
+	assert!(customer.value.name == "Zohan");
+	assert!(customer.ignored_fields.len() == 4);
+	assert!(matches!(customer.ignored_fields.get("created"), Some(Bson::DateTime(_))));
+	assert!(matches!(customer.ignored_fields.get("external_id"), Some(Bson::ObjectId(_))));
+
+	let_assert!(Ok(serialized) = bson::to_vec(&customer));
+	assert!(serialized == bytes);
+}
+
+#[test]
+fn modify_and_store() {
+	let_assert!(Ok(mut customer) = bson::from_document::<PreserveIgnoredFields<Customer, Document>>(customer()));
+	customer.value.email = "zohan@example.org".into();
+
+	let_assert!(Ok(serialized) = bson::to_document(&customer));
+	let mut expected = self::customer();
+	expected.insert("email", "zohan@example.org");
+	assert!(serialized == expected);
+}
+
+#[test]
+fn duplicate_field() {
+	let mut bytes = Vec::new();
+	let data = doc! { "name": "Zohan", "email": "zohan@example.com", "colour": "red", "colouR": "blue" };
+	let_assert!(Ok(()) = data.to_writer(&mut bytes));
+
+	// Documents can not hold duplicate keys, so rename the last key in the raw bytes.
+	let_assert!(Some(position) = bytes.windows(7).position(|window| window == b"colouR\0"));
+	bytes[position + 5] = b'r';
+
+	let_assert!(Err(e) = bson::from_slice::<PreserveIgnoredFields<Customer, Document>>(&bytes));
+	assert!(e.to_string().contains("duplicate field: \"colour\""));
+}
+
+#[test]
+fn deep() {
+	let data = doc! {
+		"id": 7_i64,
+		"customer": customer(),
+		"status": "shipped",
+	};
+	let_assert!(Ok(order) = bson::from_document::<PreserveIgnoredFieldsDeep<Order, Document>>(data.clone()));
+	assert!(order.value.id == 7);
+	assert!(order.ignored_fields.len() == 2);
+
+	let_assert!(Ok(serialized) = bson::to_document(&order));
+	assert!(serialized == data);
+}
@@ -0,0 +1,169 @@
+#![cfg(feature = "ciborium")]
+
+use assert2::{assert, let_assert};
+use ciborium::Value;
+use serde_ignored_fields::{Map, PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Reading {
+	sensor: String,
+	celsius: i32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Report {
+	device: String,
+	reading: Reading,
+}
+
+type Fields = Vec<(Value, Value)>;
+
+fn to_cbor<T: serde::Serialize>(value: &T) -> Vec<u8> {
+	let mut buffer = Vec::new();
+	let_assert!(Ok(()) = ciborium::into_writer(value, &mut buffer));
+	buffer
+}
+
+fn from_cbor<T: serde::de::DeserializeOwned + std::fmt::Debug>(data: &[u8]) -> T {
+	let_assert!(Ok(value) = ciborium::from_reader(data));
+	value
+}
+
+fn text(value: &str) -> Value {
+	Value::Text(value.into())
+}
+
+fn reading(extra: Vec<(Value, Value)>) -> Value {
+	let mut entries = vec![(text("sensor"), text("outside")), (text("celsius"), Value::from(21))];
+	entries.extend(extra);
+	Value::Map(entries)
+}
+
+#[test]
+fn integer_keys() {
+	let data = to_cbor(&reading(vec![
+		(Value::from(100), Value::from(1013)),
+		(Value::from(101), text("hPa")),
+	]));
+
+	let reading: PreserveIgnoredFields<Reading, Fields> = from_cbor(&data);
+	assert!(reading.value == Reading {
+		sensor: "outside".into(),
+		celsius: 21,
+	});
+	assert!(reading.ignored_fields == [(Value::from(100), Value::from(1013)), (Value::from(101), text("hPa"))]);
+
+	assert!(to_cbor(&reading) == data);
+}
+
+#[test]
+fn byte_string_keys() {
+	let data = to_cbor(&reading(vec![
+		(Value::Bytes(vec![0xCA, 0xFE]), Value::Bytes(vec![1, 2, 3])),
+		(Value::Bytes(b"humidity".to_vec()), Value::from(40)),
+	]));
+
+	let reading: PreserveIgnoredFields<Reading, Fields> = from_cbor(&data);
+	assert!(reading.ignored_fields == [
+		(Value::Bytes(vec![0xCA, 0xFE]), Value::Bytes(vec![1, 2, 3])),
+		(Value::Bytes(b"humidity".to_vec()), Value::from(40)),
+	]);
+
+	assert!(to_cbor(&reading) == data);
+}
+
+#[test]
+fn mixed_keys_with_format_independent_map() {
+	let data = to_cbor(&reading(vec![
+		(Value::from(100), Value::from(1013)),
+		(Value::Bytes(vec![0xCA, 0xFE]), text("coffee")),
+		(text("unit"), text("metric")),
+	]));
+
+	let reading: PreserveIgnoredFields<Reading, Map> = from_cbor(&data);
+	assert!(reading.ignored_fields.len() == 3);
+	assert!(to_cbor(&reading) == data);
+}
+
+#[test]
+fn serialize_value_map() {
+	let reading = PreserveIgnoredFields {
+		value: Reading {
+			sensor: "outside".into(),
+			celsius: 21,
+		},
+		ignored_fields: Value::Map(vec![(Value::from(100), Value::from(1013))]),
+	};
+	assert!(to_cbor(&reading) == to_cbor(&self::reading(vec![(Value::from(100), Value::from(1013))])));
+}
+
+#[test]
+fn serialize_value_non_map() {
+	let reading = PreserveIgnoredFields {
+		value: Reading {
+			sensor: "outside".into(),
+			celsius: 21,
+		},
+		ignored_fields: Value::Array(vec![Value::from(1013)]),
+	};
+	let mut buffer = Vec::new();
+	let_assert!(Err(e) = ciborium::into_writer(&reading, &mut buffer));
+	assert!(e.to_string().contains("invalid type for ignored fields: expected a CBOR map, found an array"));
+}
+
+#[test]
+fn deep() {
+	let data = to_cbor(&Value::Map(vec![
+		(text("device"), text("thermometer")),
+		(text("reading"), reading(vec![(Value::from(100), Value::from(1013))])),
+		(Value::from(200), Value::from(3)),
+	]));
+
+	let report: PreserveIgnoredFieldsDeep<Report, Fields> = from_cbor(&data);
+	assert!(report.value.reading.celsius == 21);
+	assert!(report.ignored_fields.len() == 2);
+	assert!(to_cbor(&report) == data);
+}
+
+#[test]
+fn integer_keys_below_field_count() {
+	// `serde` reads an integer key as the index of a field, but the keys of a struct with ignored fields are never indices.
+	let data = to_cbor(&reading(vec![
+		(Value::from(0), text("inside")),
+		(Value::from(1), Value::from(19)),
+		(Value::from(-1), text("negative")),
+	]));
+
+	let reading: PreserveIgnoredFields<Reading, Fields> = from_cbor(&data);
+	assert!(reading.value == Reading {
+		sensor: "outside".into(),
+		celsius: 21,
+	});
+	assert!(reading.ignored_fields == [
+		(Value::from(0), text("inside")),
+		(Value::from(1), Value::from(19)),
+		(Value::from(-1), text("negative")),
+	]);
+
+	assert!(to_cbor(&reading) == data);
+}
+
+#[test]
+fn integer_key_of_missing_field() {
+	#[derive(Debug, serde::Deserialize)]
+	struct Sample {
+		#[serde(default)]
+		sensor: Option<String>,
+		celsius: i32,
+	}
+
+	let data = to_cbor(&Value::Map(vec![
+		(Value::from(0), text("inside")),
+		(text("celsius"), Value::from(21)),
+	]));
+
+	let sample: PreserveIgnoredFields<Sample, Fields> = from_cbor(&data);
+	assert!(sample.value.sensor == None);
+	assert!(sample.value.celsius == 21);
+	assert!(sample.ignored_fields == [(Value::from(0), text("inside"))]);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, check, let_assert};
+use serde_ignored_fields::{IgnoredFieldNames, PreserveIgnoredFields};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Extension {
+	enabled: bool,
+}
+
+fn json<T: serde::de::DeserializeOwned, U: for<'de> serde_ignored_fields::DeserializeIgnoredFields<'de>>(
+	data: &str,
+) -> Result<PreserveIgnoredFields<T, U>, serde_json::Error> {
+	serde::Deserialize::deserialize(&mut serde_json::Deserializer::from_str(data))
+}
+
+const JSON: &str = r#"{"name":"Zohan","hobby":"hair-dressing","sparkles":{"enabled":true},"glitter":{"enabled":false}}"#;
+
+fn person() -> Person {
+	Person {
+		name: "Zohan".into(),
+		hobby: "hair-dressing".into(),
+	}
+}
+
+#[test]
+fn btree_map() {
+	let_assert!(Ok(deserialized) = json::<Person, BTreeMap<String, Extension>>(JSON));
+	assert!(deserialized.value == person());
+	assert!(deserialized.ignored_fields == BTreeMap::from([
+		("glitter".into(), Extension { enabled: false }),
+		("sparkles".into(), Extension { enabled: true }),
+	]));
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","hobby":"hair-dressing","glitter":{"enabled":false},"sparkles":{"enabled":true}}"#);
+}
+
+#[test]
+fn hash_map() {
+	let_assert!(Ok(deserialized) = json::<Person, HashMap<String, Extension>>(JSON));
+	assert!(deserialized.value == person());
+	assert!(deserialized.ignored_fields == HashMap::from([
+		("glitter".into(), Extension { enabled: false }),
+		("sparkles".into(), Extension { enabled: true }),
+	]));
+}
+
+#[test]
+fn vec() {
+	let_assert!(Ok(deserialized) = json::<Person, Vec<(String, Extension)>>(JSON));
+	assert!(deserialized.value == person());
+	assert!(deserialized.ignored_fields == [
+		("sparkles".into(), Extension { enabled: true }),
+		("glitter".into(), Extension { enabled: false }),
+	]);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == JSON);
+}
+
+#[test]
+fn duplicate_keys() {
+	let data = r#"{"name":"Zohan","hobby":"hair-dressing","extra":1,"extra":2}"#;
+
+	let_assert!(Ok(deserialized) = json::<Person, Vec<(String, u32)>>(data));
+	assert!(deserialized.ignored_fields == [("extra".into(), 1), ("extra".into(), 2)]);
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+
+	check!(let Err(_) = json::<Person, BTreeMap<String, u32>>(data));
+	check!(let Err(_) = json::<Person, HashMap<String, u32>>(data));
+}
+
+#[test]
+fn field_names() {
+	let data = r#"{"name":"Zohan","sparkles":{"enabled":true},"hobby":"hair-dressing","extra":1,"extra":[2]}"#;
+
+	let_assert!(Ok(deserialized) = json::<Person, IgnoredFieldNames>(data));
+	assert!(deserialized.value == person());
+	assert!(deserialized.ignored_fields.names == ["sparkles", "extra", "extra"]);
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{FieldPath, IgnoredWins, KeepOrder, PreserveIgnoredFields, PreserveIgnoredFieldsDeep, ValueWins};
+use serde_test::{assert_ser_tokens, Token};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Team {
+	lead: Person,
+}
+
+fn zohan() -> Person {
+	Person {
+		name: "Zohan".into(),
+		hobby: "hair dressing".into(),
+	}
+}
+
+fn fields(entries: &[(&str, &str)]) -> serde_json::Map<String, serde_json::Value> {
+	entries
+		.iter()
+		.map(|(key, value)| (key.to_string(), serde_json::Value::from(*value)))
+		.collect()
+}
+
+#[test]
+fn error_by_default() {
+	let person = PreserveIgnoredFields {
+		value: zohan(),
+		ignored_fields: fields(&[("colour", "red"), ("hobby", "fighting")]),
+	};
+	let_assert!(Err(e) = serde_json::to_string(&person));
+	assert!(e.to_string() == "ignored field `hobby` has the same key as a field of the serialized value");
+}
+
+#[test]
+fn no_collision() {
+	let person = PreserveIgnoredFields {
+		value: zohan(),
+		ignored_fields: fields(&[("colour", "red")]),
+	};
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == r#"{"name":"Zohan","hobby":"hair dressing","colour":"red"}"#);
+}
+
+#[test]
+fn value_wins() {
+	let person = PreserveIgnoredFields {
+		value: zohan(),
+		ignored_fields: ValueWins(fields(&[("hobby", "fighting"), ("colour", "red")])),
+	};
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == r#"{"name":"Zohan","hobby":"hair dressing","colour":"red"}"#);
+}
+
+#[test]
+fn ignored_wins() {
+	let person = PreserveIgnoredFields {
+		value: zohan(),
+		ignored_fields: IgnoredWins(fields(&[("hobby", "fighting"), ("colour", "red")])),
+	};
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == r#"{"name":"Zohan","hobby":"fighting","colour":"red"}"#);
+}
+
+#[test]
+fn ignored_wins_keeps_order() {
+	type Fields = IgnoredWins<KeepOrder<serde_json::Map<String, serde_json::Value>>>;
+	let_assert!(Ok(mut person) = serde_json::from_str::<PreserveIgnoredFields<Person, Fields>>(
+		r#"{"colour":"red","name":"Zohan","hobby":"hair dressing"}"#
+	));
+	person.ignored_fields.0.fields.insert("name".into(), "Dahan".into());
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == r#"{"colour":"red","hobby":"hair dressing","name":"Dahan"}"#);
+}
+
+#[test]
+fn ignored_wins_keeps_order_with_map_len() {
+	type Fields = IgnoredWins<KeepOrder<serde_json::Map<String, serde_json::Value>>>;
+	let_assert!(Ok(mut person) = serde_json::from_str::<PreserveIgnoredFields<Person, Fields>>(
+		r#"{"colour":"red","name":"Zohan","hobby":"hair dressing"}"#
+	));
+	person.ignored_fields.0.fields.insert("name".into(), "Dahan".into());
+	assert_ser_tokens(
+		&person,
+		&[
+			Token::Map { len: Some(3) },
+			Token::Str("colour"),
+			Token::Str("red"),
+			Token::Str("hobby"),
+			Token::Str("hair dressing"),
+			Token::Str("name"),
+			Token::Str("Dahan"),
+			Token::MapEnd,
+		],
+	);
+}
+
+#[test]
+fn value_wins_keeps_order() {
+	type Fields = ValueWins<KeepOrder<serde_json::Map<String, serde_json::Value>>>;
+	let_assert!(Ok(mut person) = serde_json::from_str::<PreserveIgnoredFields<Person, Fields>>(
+		r#"{"colour":"red","name":"Zohan","hobby":"hair dressing","size":"L"}"#
+	));
+	person.ignored_fields.0.fields.insert("hobby".into(), "fighting".into());
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == r#"{"colour":"red","name":"Zohan","hobby":"hair dressing","size":"L"}"#);
+}
+
+#[test]
+fn value_wins_keeps_order_with_map_len() {
+	type Fields = ValueWins<KeepOrder<serde_json::Map<String, serde_json::Value>>>;
+	let_assert!(Ok(mut person) = serde_json::from_str::<PreserveIgnoredFields<Person, Fields>>(
+		r#"{"colour":"red","name":"Zohan","hobby":"hair dressing","size":"L"}"#
+	));
+	person.ignored_fields.0.fields.insert("hobby".into(), "fighting".into());
+	assert_ser_tokens(
+		&person,
+		&[
+			Token::Map { len: Some(4) },
+			Token::Str("colour"),
+			Token::Str("red"),
+			Token::Str("name"),
+			Token::Str("Zohan"),
+			Token::Str("hobby"),
+			Token::Str("hair dressing"),
+			Token::Str("size"),
+			Token::Str("L"),
+			Token::MapEnd,
+		],
+	);
+}
+
+#[test]
+fn map_len_of_map_with_collision() {
+	let map = PreserveIgnoredFields {
+		value: BTreeMap::from([(1, "one"), (2, "two")]),
+		ignored_fields: ValueWins(fields(&[("2", "deux"), ("3", "trois")])),
+	};
+	assert_ser_tokens(
+		&map,
+		&[
+			Token::Map { len: Some(3) },
+			Token::I32(1),
+			Token::Str("one"),
+			Token::I32(2),
+			Token::Str("two"),
+			Token::Str("3"),
+			Token::Str("trois"),
+			Token::MapEnd,
+		],
+	);
+}
+
+#[test]
+fn integer_keys() {
+	let map = PreserveIgnoredFields {
+		value: BTreeMap::from([(1, "one"), (2, "two")]),
+		ignored_fields: IgnoredWins(fields(&[("2", "deux")])),
+	};
+	let_assert!(Ok(serialized) = serde_json::to_string(&map));
+	assert!(serialized == r#"{"1":"one","2":"deux"}"#);
+}
+
+#[test]
+fn nested_collision() {
+	let_assert!(Ok(mut team) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, serde_json::Map<String, serde_json::Value>>>(
+		r#"{"lead":{"name":"Zohan","hobby":"hair dressing","colour":"red"}}"#
+	));
+	let_assert!(Some(lead) = team.ignored_fields.get_mut(&FieldPath::from_iter(["lead"])));
+	lead.insert("name".into(), "Dahan".into());
+	let_assert!(Err(e) = serde_json::to_string(&team));
+	assert!(e.to_string() == "ignored field `name` has the same key as a field of the serialized value");
+}
@@ -0,0 +1,167 @@
+#![cfg(feature = "serde_yaml")]
+
+use assert2::{assert, let_assert};
+use indoc::indoc;
+use serde_ignored_fields::{FieldPath, PathSegment, PreserveIgnoredFieldsDeep};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Config {
+	name: String,
+	network: Network,
+	servers: Vec<Server>,
+	backup: Option<Server>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Network {
+	port: u16,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Server {
+	host: String,
+}
+
+fn yaml<T: serde::de::DeserializeOwned>(
+	data: &str,
+) -> Result<PreserveIgnoredFieldsDeep<T, serde_yaml::Mapping>, serde_yaml::Error> {
+	serde::Deserialize::deserialize(serde_yaml::Deserializer::from_str(data))
+}
+
+fn mapping(data: &str) -> serde_yaml::Mapping {
+	serde_yaml::from_str(data).unwrap()
+}
+
+fn path<const N: usize>(segments: [PathSegment; N]) -> FieldPath {
+	FieldPath::from_iter(segments)
+}
+
+fn config() -> Config {
+	Config {
+		name: "main".into(),
+		network: Network { port: 80 },
+		servers: vec![
+			Server { host: "alpha".into() },
+			Server { host: "beta".into() },
+		],
+		backup: Some(Server { host: "gamma".into() }),
+	}
+}
+
+fn ignored_fields() -> BTreeMap<FieldPath, serde_yaml::Mapping> {
+	BTreeMap::from([
+		(FieldPath::new(), mapping("version: 2")),
+		(path(["network".into()]), mapping("mtu: 1500")),
+		(path(["servers".into(), 1.into()]), mapping("weight: 3")),
+		(path(["backup".into()]), mapping("cold: true")),
+	])
+}
+
+const YAML: &str = indoc!(r#"
+	name: main
+	network:
+	  port: 80
+	  mtu: 1500
+	servers:
+	- host: alpha
+	- host: beta
+	  weight: 3
+	backup:
+	  host: gamma
+	  cold: true
+	version: 2
+"#);
+
+#[test]
+fn deserialize_nested() {
+	let_assert!(Ok(deserialized) = yaml::<Config>(YAML));
+	assert!(deserialized.value == config());
+	assert!(deserialized.ignored_fields == ignored_fields());
+}
+
+#[test]
+fn serialize_nested() {
+	let value = PreserveIgnoredFieldsDeep {
+		value: config(),
+		ignored_fields: ignored_fields(),
+	};
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&value));
+	assert!(serialized == YAML);
+}
+
+#[test]
+fn deserialize_map_of_structs() {
+	let_assert!(Ok(deserialized) = yaml::<BTreeMap<String, Server>>(indoc!(r#"
+		first:
+		  host: alpha
+		second:
+		  host: beta
+		  weight: 3
+	"#)));
+
+	assert!(deserialized.value["second"].host == "beta");
+	assert!(deserialized.ignored_fields == BTreeMap::from([
+		(path(["second".into()]), mapping("weight: 3")),
+	]));
+}
+
+#[test]
+fn path_display() {
+	let path = path(["servers".into(), 1.into(), "host".into()]);
+	assert!(path.to_string() == "servers[1].host");
+	assert!(FieldPath::new().to_string() == "");
+}
+
+#[test]
+fn round_trip_struct_variant() {
+	#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+	enum Backend {
+		Remote { host: String },
+	}
+
+	#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+	struct Storage {
+		backend: Backend,
+	}
+
+	let data = indoc!(r#"
+		backend: !Remote
+		  host: alpha
+		  weight: 3
+	"#);
+
+	let_assert!(Ok(deserialized) = yaml::<Storage>(data));
+	assert!(deserialized.value.backend == Backend::Remote { host: "alpha".into() });
+	assert!(deserialized.ignored_fields == BTreeMap::from([
+		(path(["backend".into()]), mapping("weight: 3")),
+	]));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn serialize_none_with_ignored_fields() {
+	let mut value = config();
+	value.backup = None;
+	let value = PreserveIgnoredFieldsDeep {
+		value,
+		ignored_fields: ignored_fields(),
+	};
+
+	let_assert!(Err(e) = serde_yaml::to_string(&value));
+	assert!(e.to_string() == "backup: invalid type `none`: can only re-serialize a map or struct with ignored fields");
+}
+
+#[test]
+fn serialize_unit_with_ignored_fields() {
+	let value = PreserveIgnoredFieldsDeep {
+		value: BTreeMap::from([("network", ())]),
+		ignored_fields: BTreeMap::from([(path(["network".into()]), mapping("mtu: 1500"))]),
+	};
+
+	let_assert!(Err(e) = serde_yaml::to_string(&value));
+	assert!(e.to_string() == "network: invalid type `unit`: can only re-serialize a map or struct with ignored fields");
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "serde_yaml")]
+
+use assert2::{assert, let_assert};
+use indoc::indoc;
+use serde_ignored_fields::DenyIgnoredFields;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+}
+
+fn yaml<T: serde::de::DeserializeOwned>(data: &str) -> Result<DenyIgnoredFields<T>, serde_yaml::Error> {
+	serde::Deserialize::deserialize(serde_yaml::Deserializer::from_str(data))
+}
+
+#[test]
+fn no_ignored_fields() {
+	let_assert!(Ok(deserialized) = yaml::<Person>(indoc!(
+		r#"
+			name: Zohan
+			hobby: hair dressing
+		"#
+	)));
+	assert!(deserialized.value == Person {
+		name: "Zohan".into(),
+		hobby: "hair dressing".into(),
+	});
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == indoc!(
+		r#"
+			name: Zohan
+			hobby: hair dressing
+		"#
+	));
+}
+
+#[test]
+fn report_all_ignored_fields() {
+	let_assert!(Err(e) = yaml::<Person>(indoc!(
+		r#"
+			name: Zohan
+			hobyy: hair dressing
+			hobby: hair dressing
+			colour: red
+			5: five
+		"#
+	)));
+	assert!(e.to_string().starts_with("unknown fields: `hobyy` (did you mean `hobby`?), `colour`, `5`"));
+}
+
+#[test]
+fn report_single_ignored_field() {
+	let_assert!(Err(e) = yaml::<Person>(indoc!(
+		r#"
+			name: Zohan
+			hobby: hair dressing
+			colour: red
+		"#
+	)));
+	assert!(e.to_string().starts_with("unknown field `colour`"));
+}
+
+#[test]
+fn error_of_wrapped_type_takes_precedence() {
+	let_assert!(Err(e) = yaml::<Person>(indoc!(
+		r#"
+			name: Zohan
+			colour: red
+		"#
+	)));
+	assert!(e.to_string().starts_with("missing field `hobby`"));
+}
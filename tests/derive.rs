@@ -0,0 +1,102 @@
+#![cfg(feature = "derive")]
+
+use assert2::{assert, let_assert};
+use indoc::indoc;
+use serde_ignored_fields::PreserveIgnored;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize, PreserveIgnored)]
+#[serde(remote = "Self")]
+struct Person {
+	name: String,
+	hobby: String,
+
+	#[ignored_fields]
+	#[serde(skip)]
+	extra: serde_yaml::Mapping,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize, PreserveIgnored)]
+#[serde(remote = "Self", rename_all = "kebab-case")]
+struct Wrapper<T> {
+	inner_value: T,
+
+	#[ignored_fields]
+	#[serde(skip)]
+	extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[test]
+fn round_trip() {
+	let yaml = indoc!(r#"
+		name: Zohan
+		hobby: hair dresser
+		hair: fabulous
+	"#);
+
+	let_assert!(Ok(person) = serde_yaml::from_str::<Person>(yaml));
+	assert!(person.name == "Zohan");
+	assert!(person.hobby == "hair dresser");
+	assert!(person.extra.len() == 1);
+	assert!(person.extra["hair"] == "fabulous");
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&person));
+	assert!(serialized == yaml);
+}
+
+#[test]
+fn round_trip_generic() {
+	let json = r#"{"inner-value":5,"extra":true}"#;
+
+	let_assert!(Ok(wrapper) = serde_json::from_str::<Wrapper<u32>>(json));
+	assert!(wrapper.inner_value == 5);
+	assert!(wrapper.extra["extra"] == true);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&wrapper));
+	assert!(serialized == json);
+}
+
+#[test]
+fn nested() {
+	#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+	struct Team {
+		members: Vec<Person>,
+	}
+
+	let yaml = indoc!(r#"
+		members:
+		- name: Zohan
+		  hobby: hair dresser
+		  hair: fabulous
+		- name: Scrappy Coco
+		  hobby: hacky sack
+	"#);
+
+	let_assert!(Ok(team) = serde_yaml::from_str::<Team>(yaml));
+	assert!(team.members[0].extra["hair"] == "fabulous");
+	assert!(team.members[1].extra.is_empty());
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&team));
+	assert!(serialized == yaml);
+}
+
+#[serde_ignored_fields::preserve_ignored]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Pet {
+	pet_name: String,
+
+	#[ignored_fields]
+	extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[test]
+fn attribute_adds_serde_attributes() {
+	let json = r#"{"pet-name":"Scrappy","kind":"dog"}"#;
+
+	let_assert!(Ok(pet) = serde_json::from_str::<Pet>(json));
+	assert!(pet.pet_name == "Scrappy");
+	assert!(pet.extra["kind"] == "dog");
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&pet));
+	assert!(serialized == json);
+}
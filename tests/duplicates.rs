@@ -0,0 +1,133 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{
+	CollectAll, FirstWins, IgnoredFieldNames, KeepOrder, LastWins, Limited, PreserveIgnoredFields, WithMetadata,
+};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Hobbyist {
+	name: String,
+	hobby: String,
+}
+
+type Entries = Vec<(String, serde_json::Value)>;
+
+fn json<T: serde::de::DeserializeOwned, U: for<'de> serde_ignored_fields::DeserializeIgnoredFields<'de>>(
+	data: &str,
+) -> Result<PreserveIgnoredFields<T, U>, serde_json::Error> {
+	serde::Deserialize::deserialize(&mut serde_json::Deserializer::from_str(data))
+}
+
+const JSON: &str = r#"{"name":"Zohan","color":"red","size":1,"color":"blue","color":"green"}"#;
+
+#[test]
+fn error_by_default() {
+	let_assert!(Err(e) = json::<Person, BTreeMap<String, serde_json::Value>>(JSON));
+	assert!(e.to_string().contains("duplicate field: \"color\""));
+}
+
+#[test]
+fn first_wins() {
+	let_assert!(Ok(deserialized) = json::<Person, FirstWins<serde_json::Map<String, serde_json::Value>>>(JSON));
+	assert!(deserialized.value.name == "Zohan");
+	assert!(deserialized.ignored_fields.0.len() == 2);
+	assert!(deserialized.ignored_fields.0["color"] == "red");
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","color":"red","size":1}"#);
+}
+
+#[test]
+fn last_wins() {
+	let_assert!(Ok(deserialized) = json::<Person, LastWins<serde_json::Map<String, serde_json::Value>>>(JSON));
+	assert!(deserialized.value.name == "Zohan");
+	assert!(deserialized.ignored_fields.0.len() == 2);
+	assert!(deserialized.ignored_fields.0["color"] == "green");
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","color":"green","size":1}"#);
+}
+
+#[test]
+fn collect_all() {
+	let_assert!(Ok(deserialized) = json::<Person, CollectAll<BTreeMap<String, Vec<serde_json::Value>>>>(JSON));
+	assert!(deserialized.value.name == "Zohan");
+	assert!(deserialized.ignored_fields.0 == BTreeMap::from([
+		("color".into(), vec!["red".into(), "blue".into(), "green".into()]),
+		("size".into(), vec![1.into()]),
+	]));
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","color":"red","color":"blue","color":"green","size":1}"#);
+}
+
+#[test]
+fn last_wins_keeps_order() {
+	let data = r#"{"name":"Zohan","a":1,"hobby":"hair dressing","b":2,"a":3}"#;
+	let_assert!(Ok(deserialized) = json::<Hobbyist, LastWins<KeepOrder<Entries>>>(data));
+	assert!(deserialized.ignored_fields.0.fields == [("a".into(), 3.into()), ("b".into(), 2.into())]);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","a":3,"hobby":"hair dressing","b":2}"#);
+}
+
+#[test]
+fn last_wins_keeps_metadata() {
+	let_assert!(Ok(deserialized) = json::<Person, LastWins<WithMetadata<Entries>>>(JSON));
+	assert!(deserialized.ignored_fields.0.metadata.name == Some("Person"));
+	assert!(deserialized.ignored_fields.0.metadata.fields == ["name"]);
+	assert!(deserialized.ignored_fields.0.fields == [("color".into(), "green".into()), ("size".into(), 1.into())]);
+}
+
+#[test]
+fn last_wins_keeps_limits() {
+	let_assert!(Ok(deserialized) = json::<Person, LastWins<Limited<Entries, 2, 4, 1024>>>(JSON));
+	assert!(deserialized.ignored_fields.0.inner() == &[("color".into(), "green".into()), ("size".into(), 1.into())]);
+
+	let data = r#"{"name":"Zohan","color":"red","size":1,"color":"blue","shape":"round"}"#;
+	let_assert!(Err(e) = json::<Person, LastWins<Limited<Entries, 2, 4, 1024>>>(data));
+	assert!(e.to_string().starts_with("too many ignored fields, the maximum is 2"));
+
+	// Replaced values still count towards the size limit.
+	let data = r#"{"name":"Zohan","a":"0123456789","a":"0123456789","a":"0123456789"}"#;
+	let_assert!(Err(e) = json::<Person, LastWins<Limited<Entries, 2, 4, 30>>>(data));
+	assert!(e.to_string().starts_with("ignored fields are too large, the maximum size is 30 bytes"));
+}
+
+#[test]
+fn collect_all_keeps_order() {
+	let data = r#"{"name":"Zohan","a":1,"hobby":"hair dressing","b":2,"a":3}"#;
+	let_assert!(Ok(deserialized) = json::<Hobbyist, CollectAll<KeepOrder<Vec<(String, Vec<serde_json::Value>)>>>>(data));
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","a":1,"a":3,"hobby":"hair dressing","b":2}"#);
+}
+
+#[test]
+fn collect_all_keeps_limits() {
+	type Limits<const MAX_SIZE: usize> = CollectAll<Limited<BTreeMap<String, Vec<serde_json::Value>>, 2, 4, MAX_SIZE>>;
+
+	let data = r#"{"name":"Zohan","a":"0123456789","a":"0123456789","a":"0123456789"}"#;
+	let_assert!(Ok(deserialized) = json::<Person, Limits<1024>>(data));
+	assert!(deserialized.ignored_fields.0.inner()["a"].len() == 3);
+
+	// Each value counts as a sequence with one element, since that is what the wrapped type stores.
+	let_assert!(Err(e) = json::<Person, Limits<30>>(data));
+	assert!(e.to_string().starts_with("ignored fields are too large, the maximum size is 30 bytes"));
+}
+
+#[test]
+fn error_without_get_mut() {
+	let_assert!(Err(e) = json::<Person, FirstWins<IgnoredFieldNames>>(JSON));
+	assert!(e.to_string().starts_with("`FirstWins` can not detect duplicate ignored fields: the wrapped type does not support `get_mut()`"));
+
+	let_assert!(Err(e) = json::<Person, LastWins<IgnoredFieldNames>>(JSON));
+	assert!(e.to_string().starts_with("`LastWins` can not detect duplicate ignored fields"));
+}
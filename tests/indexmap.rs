@@ -0,0 +1,40 @@
+#![cfg(feature = "indexmap")]
+
+use assert2::{assert, check, let_assert};
+use indexmap::IndexMap;
+use serde_ignored_fields::PreserveIgnoredFields;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+}
+
+fn yaml<T: serde::de::DeserializeOwned>(
+	data: &str,
+) -> Result<PreserveIgnoredFields<T, IndexMap<String, serde_yaml::Value>>, serde_yaml::Error> {
+	serde::Deserialize::deserialize(serde_yaml::Deserializer::from_str(data))
+}
+
+#[test]
+fn round_trip_keeps_order() {
+	let data = "name: Zohan\nhobby: hair-dressing\nzebra: 1\napple: 2\nmango: 3\n";
+
+	let_assert!(Ok(deserialized) = yaml::<Person>(data));
+	assert!(deserialized.value == Person {
+		name: "Zohan".into(),
+		hobby: "hair-dressing".into(),
+	});
+	assert!(deserialized.ignored_fields.keys().collect::<Vec<_>>() == ["zebra", "apple", "mango"]);
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn error_on_duplicate_field() {
+	let data = r#"{"name":"Zohan","hobby":"hair-dressing","extra":1,"extra":2}"#;
+	let result: Result<PreserveIgnoredFields<Person, IndexMap<String, serde_json::Value>>, _> = serde_json::from_str(data);
+	let_assert!(Err(e) = result);
+	check!(e.to_string().contains("duplicate field: \"extra\""));
+}
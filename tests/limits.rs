@@ -0,0 +1,157 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{CollectAll, KeepOrder, Limited, PreserveIgnoredFields, PreserveIgnoredFieldsDeep, WithMetadata};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Hobbyist {
+	name: String,
+	hobby: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Team {
+	lead: Person,
+}
+
+type Fields<const MAX_FIELDS: usize, const MAX_DEPTH: usize, const MAX_SIZE: usize> =
+	Limited<serde_json::Map<String, serde_json::Value>, MAX_FIELDS, MAX_DEPTH, MAX_SIZE>;
+
+fn json<T: serde::de::DeserializeOwned, U: for<'de> serde_ignored_fields::DeserializeIgnoredFields<'de>>(
+	data: &str,
+) -> Result<PreserveIgnoredFields<T, U>, serde_json::Error> {
+	serde::Deserialize::deserialize(&mut serde_json::Deserializer::from_str(data))
+}
+
+#[test]
+fn within_limits() {
+	let_assert!(Ok(deserialized) = json::<Person, Fields<2, 2, 64>>(r#"{"name":"Zohan","hair":{"style":["curly"]},"age":42}"#));
+	assert!(deserialized.value.name == "Zohan");
+	assert!(deserialized.ignored_fields.inner().len() == 2);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","hair":{"style":["curly"]},"age":42}"#);
+}
+
+#[test]
+fn too_many_fields() {
+	let_assert!(Err(e) = json::<Person, Fields<2, 2, 1024>>(r#"{"name":"Zohan","a":1,"b":2,"c":3}"#));
+	assert!(e.to_string().starts_with("too many ignored fields, the maximum is 2"));
+}
+
+#[test]
+fn too_deep() {
+	let_assert!(Ok(_) = json::<Person, Fields<8, 2, 1024>>(r#"{"name":"Zohan","a":[[1]]}"#));
+	let_assert!(Err(e) = json::<Person, Fields<8, 2, 1024>>(r#"{"name":"Zohan","a":[[[1]]]}"#));
+	assert!(e.to_string().starts_with("ignored field is nested too deeply, the maximum depth is 2"));
+	let_assert!(Err(e) = json::<Person, Fields<8, 0, 1024>>(r#"{"name":"Zohan","a":{}}"#));
+	assert!(e.to_string().starts_with("ignored field is nested too deeply, the maximum depth is 0"));
+}
+
+#[test]
+fn too_large() {
+	// The keys count towards the size too.
+	let_assert!(Ok(_) = json::<Person, Fields<8, 2, 10>>(r#"{"name":"Zohan","a":"123456789"}"#));
+	let_assert!(Err(e) = json::<Person, Fields<8, 2, 10>>(r#"{"name":"Zohan","a":"1234567890"}"#));
+	assert!(e.to_string().starts_with("ignored fields are too large, the maximum size is 10 bytes"));
+
+	// The size is the total of all ignored fields.
+	let_assert!(Err(e) = json::<Person, Fields<8, 2, 10>>(r#"{"name":"Zohan","a":"1234","b":"12345"}"#));
+	assert!(e.to_string().starts_with("ignored fields are too large, the maximum size is 10 bytes"));
+}
+
+#[test]
+fn deep() {
+	let data = r#"{"lead":{"name":"Zohan","a":1},"b":2}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, Fields<2, 0, 64>>>(data));
+	assert!(deserialized.value.lead.name == "Zohan");
+	assert!(deserialized.ignored_fields.len() == 2);
+
+	let data = r#"{"lead":{"name":"Zohan","a":1,"b":2,"c":3}}"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, Fields<2, 0, 64>>>(data));
+	assert!(e.to_string().starts_with("lead: too many ignored fields, the maximum is 2"));
+}
+
+#[test]
+fn deep_total() {
+	// The limits apply to the ignored fields of all nesting levels together.
+	let data = r#"{"lead":{"name":"Zohan","a":1,"b":2},"c":3}"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, Fields<2, 0, 64>>>(data));
+	assert!(e.to_string().starts_with("too many ignored fields, the maximum is 2"));
+
+	let data = r#"[{"name":"Zohan","a":1},{"name":"Dahan","b":2},{"name":"Oori","c":3}]"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Vec<Person>, Fields<2, 0, 64>>>(data));
+	assert!(e.to_string().starts_with("[2]: too many ignored fields, the maximum is 2"));
+
+	let data = r#"[{"name":"Zohan","a":"1234"},{"name":"Dahan","b":"12345"}]"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Vec<Person>, Fields<8, 0, 10>>>(data));
+	assert!(e.to_string().starts_with("ignored fields are too large, the maximum size is 10 bytes"));
+}
+
+#[test]
+fn stacked_keep_order_limited_collect_all() {
+	type Stacked<const MAX_FIELDS: usize> =
+		KeepOrder<Limited<CollectAll<Vec<(String, Vec<serde_json::Value>)>>, MAX_FIELDS, 2, 1024>>;
+
+	let data = r#"{"name":"Zohan","a":1,"hobby":"hair dressing","b":2,"a":3}"#;
+	let_assert!(Ok(deserialized) = json::<Hobbyist, Stacked<3>>(data));
+	assert!(deserialized.ignored_fields.fields.inner().0 == [
+		("a".to_string(), vec![1.into(), 3.into()]),
+		("b".to_string(), vec![2.into()]),
+	]);
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","a":1,"a":3,"hobby":"hair dressing","b":2}"#);
+
+	// Each collected value counts as a field.
+	let_assert!(Err(e) = json::<Hobbyist, Stacked<2>>(data));
+	assert!(e.to_string().starts_with("too many ignored fields, the maximum is 2"));
+}
+
+#[test]
+fn stacked_with_metadata_limited() {
+	type Stacked<const MAX_FIELDS: usize> = WithMetadata<Fields<MAX_FIELDS, 2, 1024>>;
+
+	let_assert!(Ok(deserialized) = json::<Person, Stacked<1>>(r#"{"name":"Zohan","a":1}"#));
+	assert!(deserialized.metadata().name == Some("Person"));
+	assert!(deserialized.metadata().fields == ["name"]);
+	assert!(deserialized.ignored_fields.fields.inner().len() == 1);
+
+	let_assert!(Err(e) = json::<Person, Stacked<1>>(r#"{"name":"Zohan","a":1,"b":2}"#));
+	assert!(e.to_string().starts_with("too many ignored fields, the maximum is 1"));
+}
+
+#[test]
+fn stacked_limited_with_metadata_keep_order() {
+	type Stacked<const MAX_SIZE: usize> =
+		Limited<WithMetadata<KeepOrder<serde_json::Map<String, serde_json::Value>>>, 8, 2, MAX_SIZE>;
+
+	// The metadata and positions are recorded through the limits.
+	let data = r#"{"name":"Zohan","a":1,"hobby":"hair dressing","b":2}"#;
+	let_assert!(Ok(deserialized) = json::<Hobbyist, Stacked<1024>>(data));
+	assert!(deserialized.ignored_fields.inner().metadata.name == Some("Hobbyist"));
+	assert!(deserialized.ignored_fields.inner().metadata.fields == ["name", "hobby"]);
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+
+	let_assert!(Err(e) = json::<Hobbyist, Stacked<16>>(data));
+	assert!(e.to_string().starts_with("ignored fields are too large, the maximum size is 16 bytes"));
+}
+
+#[test]
+fn stacked_deep_total() {
+	// The limits still apply to all nesting levels together when wrapped.
+	type Stacked = KeepOrder<WithMetadata<Fields<2, 0, 64>>>;
+
+	let data = r#"{"lead":{"name":"Zohan","a":1},"b":2}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, Stacked>>(data));
+	assert!(deserialized.ignored_fields.len() == 2);
+
+	let data = r#"{"lead":{"name":"Zohan","a":1,"b":2},"c":3}"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, Stacked>>(data));
+	assert!(e.to_string().starts_with("too many ignored fields, the maximum is 2"));
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "serde_yaml")]
+
+use assert2::{assert, let_assert};
+use indoc::indoc;
+use serde_ignored_fields::{FieldPath, Metadata, PreserveIgnoredFields, PreserveIgnoredFieldsDeep, WithMetadata};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct ServerConfig {
+	host: String,
+	port: u16,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Config {
+	server: ServerConfig,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+enum Listener {
+	Tcp { port: u16 },
+	Unix { path: String },
+}
+
+type Fields = WithMetadata<serde_yaml::Mapping>;
+
+#[test]
+fn struct_metadata() {
+	let data = indoc!(
+		r#"
+			host: localhost
+			port: 8080
+			tls: true
+		"#
+	);
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<ServerConfig, Fields>>(data));
+	assert!(deserialized.metadata() == &Metadata {
+		name: Some("ServerConfig"),
+		fields: &["host", "port"],
+		variants: &[],
+	});
+	assert!(deserialized.ignored_fields.fields["tls"] == true);
+
+	// The metadata is not serialized.
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn enum_metadata() {
+	let data = indoc!(
+		r#"
+			!Tcp
+			port: 8080
+			host: localhost
+		"#
+	);
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Listener, Fields>>(data));
+	assert!(deserialized.metadata() == &Metadata {
+		name: Some("Listener"),
+		fields: &["port"],
+		variants: &["Tcp", "Unix"],
+	});
+	assert!(deserialized.ignored_fields.fields["host"] == "localhost");
+}
+
+#[test]
+fn nested_metadata() {
+	let data = indoc!(
+		r#"
+			server:
+			  host: localhost
+			  prot: 8080
+			  port: 8080
+			sever: {}
+		"#
+	);
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFieldsDeep<Config, Fields>>(data));
+
+	let root = &deserialized.ignored_fields[&FieldPath::new()].metadata;
+	assert!(root.name == Some("Config"));
+	assert!(root.suggest("sever") == Some("server"));
+
+	let server = &deserialized.ignored_fields[&FieldPath::from_iter(["server"])].metadata;
+	assert!(server.name == Some("ServerConfig"));
+	assert!(server.suggest("prot") == Some("port"));
+}
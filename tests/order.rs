@@ -0,0 +1,147 @@
+#![cfg(feature = "serde_yaml")]
+
+use assert2::{assert, let_assert};
+use indoc::indoc;
+use serde_ignored_fields::{FieldPosition, KeepOrder, PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	age: Option<u32>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Team {
+	lead: Person,
+	members: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+enum Shape {
+	Circle { radius: u32, center: u32 },
+}
+
+type Fields = KeepOrder<serde_yaml::Mapping>;
+
+#[test]
+fn keep_order() {
+	let data = indoc!(
+		r#"
+			first: 1
+			name: Zohan
+			colour: red
+			sparkles: true
+			hobby: hair dressing
+			last: 2
+		"#
+	);
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Person, Fields>>(data));
+	assert!(deserialized.ignored_fields.position_of("first") == FieldPosition::Start);
+	assert!(deserialized.ignored_fields.position_of("colour") == FieldPosition::After("name"));
+	assert!(deserialized.ignored_fields.position_of("sparkles") == FieldPosition::After("name"));
+	assert!(deserialized.ignored_fields.position_of("last") == FieldPosition::After("hobby"));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn missing_field_goes_at_the_end() {
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Person, Fields>>(indoc!(
+		r#"
+			name: Zohan
+			age: null
+			colour: red
+			hobby: hair dressing
+		"#
+	)));
+	assert!(deserialized.ignored_fields.position_of("colour") == FieldPosition::After("age"));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == indoc!(
+		r#"
+			name: Zohan
+			hobby: hair dressing
+			colour: red
+		"#
+	));
+}
+
+#[test]
+fn without_keep_order() {
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Person, serde_yaml::Mapping>>(indoc!(
+		r#"
+			name: Zohan
+			colour: red
+			hobby: hair dressing
+		"#
+	)));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == indoc!(
+		r#"
+			name: Zohan
+			hobby: hair dressing
+			colour: red
+		"#
+	));
+}
+
+#[test]
+fn keep_order_of_struct_variant() {
+	let data = indoc!(
+		r#"
+			!Circle
+			radius: 4
+			colour: red
+			center: 0
+		"#
+	);
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Shape, Fields>>(data));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn keep_order_deep() {
+	let data = indoc!(
+		r#"
+			lead:
+			  name: Zohan
+			  colour: red
+			  hobby: hair dressing
+			team_name: Barbers
+			members: 3
+		"#
+	);
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFieldsDeep<Team, Fields>>(data));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn keep_order_of_many_ignored_fields() {
+	let mut data = String::new();
+	for i in 0..5_000 {
+		data += &format!("before{i}: {i}\n");
+	}
+	data += "name: Zohan\n";
+	for i in 0..5_000 {
+		data += &format!("between{i}: {i}\n");
+	}
+	data += "hobby: hair dressing\n";
+	for i in 0..5_000 {
+		data += &format!("after{i}: {i}\n");
+	}
+
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Person, Fields>>(&data));
+	assert!(deserialized.ignored_fields.fields.len() == 15_000);
+	assert!(deserialized.ignored_fields.position_of("between1234") == FieldPosition::After("name"));
+
+	let_assert!(Ok(serialized) = serde_yaml::to_string(&deserialized));
+	assert!(serialized == data);
+}
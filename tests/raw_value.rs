@@ -0,0 +1,74 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+use serde_json::value::RawValue;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+}
+
+const JSON: &str = r#"{"name":"Zohan","score":1.50e3,"id":123456789012345678901234567890,"motto":"caf\u00e9","nested":{ "b" : [1,2] }}"#;
+
+#[test]
+fn round_trip_boxed_raw_value() {
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Person, Vec<(String, Box<RawValue>)>>>(JSON));
+	assert!(deserialized.value.name == "Zohan");
+	assert!(deserialized.ignored_fields.len() == 4);
+	assert!(deserialized.ignored_fields[0].1.get() == "1.50e3");
+	assert!(deserialized.ignored_fields[1].1.get() == "123456789012345678901234567890");
+	assert!(deserialized.ignored_fields[2].1.get() == r#""caf\u00e9""#);
+	assert!(deserialized.ignored_fields[3].1.get() == r#"{ "b" : [1,2] }"#);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == JSON);
+}
+
+#[test]
+fn round_trip_borrowed_raw_value() {
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Person, BTreeMap<String, &RawValue>>>(JSON));
+	assert!(deserialized.ignored_fields["score"].get() == "1.50e3");
+	assert!(deserialized.ignored_fields["motto"].get() == r#""caf\u00e9""#);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","id":123456789012345678901234567890,"motto":"caf\u00e9","nested":{ "b" : [1,2] },"score":1.50e3}"#);
+}
+
+#[test]
+fn round_trip_raw_value_in_struct_variant() {
+	#[derive(Debug, serde::Deserialize, serde::Serialize)]
+	enum Score {
+		Exact { value: Box<RawValue> },
+	}
+
+	let data = r#"{"Exact":{"value":1.50,"extra":true}}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Score, serde_json::Map<String, serde_json::Value>>>(data));
+	let Score::Exact { value } = &deserialized.value;
+	assert!(value.get() == "1.50");
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn round_trip_raw_value_in_nested_struct_variant() {
+	#[derive(Debug, serde::Deserialize, serde::Serialize)]
+	enum Score {
+		Exact { value: Box<RawValue> },
+	}
+
+	#[derive(Debug, serde::Deserialize, serde::Serialize)]
+	struct Player {
+		score: Score,
+	}
+
+	let data = r#"{"score":{"Exact":{"value":1.50,"extra":true}}}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Player, serde_json::Map<String, serde_json::Value>>>(data));
+	let Score::Exact { value } = &deserialized.value.score;
+	assert!(value.get() == "1.50");
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+}
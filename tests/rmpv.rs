@@ -0,0 +1,122 @@
+#![cfg(feature = "rmpv")]
+
+use assert2::{assert, let_assert};
+use rmpv::Value;
+use serde_ignored_fields::{Map, PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Request {
+	method: String,
+	id: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Envelope {
+	sender: String,
+	request: Request,
+}
+
+type Fields = Vec<(Value, Value)>;
+
+fn to_msgpack<T: serde::Serialize>(value: &T) -> Vec<u8> {
+	let_assert!(Ok(data) = rmp_serde::to_vec_named(value));
+	data
+}
+
+fn from_msgpack<T: serde::de::DeserializeOwned + std::fmt::Debug>(data: &[u8]) -> T {
+	let_assert!(Ok(value) = rmp_serde::from_slice(data));
+	value
+}
+
+fn request(extra: Vec<(Value, Value)>) -> Value {
+	let mut entries = vec![(Value::from("method"), Value::from("ping")), (Value::from("id"), Value::from(7))];
+	entries.extend(extra);
+	Value::Map(entries)
+}
+
+#[test]
+fn round_trip() {
+	let data = to_msgpack(&request(vec![
+		(Value::from("deadline"), Value::from(1500)),
+		(Value::from("trace"), Value::Map(vec![(Value::from("span"), Value::from("a1b2"))])),
+	]));
+
+	let request: PreserveIgnoredFields<Request, Fields> = from_msgpack(&data);
+	assert!(request.value == Request {
+		method: "ping".into(),
+		id: 7,
+	});
+	assert!(request.ignored_fields == [
+		(Value::from("deadline"), Value::from(1500)),
+		(Value::from("trace"), Value::Map(vec![(Value::from("span"), Value::from("a1b2"))])),
+	]);
+
+	assert!(to_msgpack(&request) == data);
+}
+
+#[test]
+fn non_string_keys() {
+	let data = to_msgpack(&request(vec![
+		(Value::from(100), Value::from(true)),
+		(Value::Binary(vec![0xCA, 0xFE]), Value::Binary(vec![1, 2, 3])),
+	]));
+
+	let request: PreserveIgnoredFields<Request, Fields> = from_msgpack(&data);
+	assert!(request.ignored_fields == [
+		(Value::from(100), Value::from(true)),
+		(Value::Binary(vec![0xCA, 0xFE]), Value::Binary(vec![1, 2, 3])),
+	]);
+
+	assert!(to_msgpack(&request) == data);
+}
+
+#[test]
+fn format_independent_map() {
+	let data = to_msgpack(&request(vec![
+		(Value::from("deadline"), Value::from(1500)),
+		(Value::from("tags"), Value::Array(vec![Value::from("urgent")])),
+	]));
+
+	let request: PreserveIgnoredFields<Request, Map> = from_msgpack(&data);
+	assert!(request.ignored_fields.len() == 2);
+	assert!(to_msgpack(&request) == data);
+}
+
+#[test]
+fn serialize_value_map() {
+	let request = PreserveIgnoredFields {
+		value: Request {
+			method: "ping".into(),
+			id: 7,
+		},
+		ignored_fields: Value::Map(vec![(Value::from("deadline"), Value::from(1500))]),
+	};
+	assert!(to_msgpack(&request) == to_msgpack(&self::request(vec![(Value::from("deadline"), Value::from(1500))])));
+}
+
+#[test]
+fn serialize_value_non_map() {
+	let request = PreserveIgnoredFields {
+		value: Request {
+			method: "ping".into(),
+			id: 7,
+		},
+		ignored_fields: Value::from("deadline"),
+	};
+	let_assert!(Err(e) = rmp_serde::to_vec_named(&request));
+	assert!(e.to_string() == "invalid type for ignored fields: expected a MessagePack map, found a string");
+}
+
+#[test]
+fn deep() {
+	let data = to_msgpack(&Value::Map(vec![
+		(Value::from("sender"), Value::from("proxy")),
+		(Value::from("request"), request(vec![(Value::from("deadline"), Value::from(1500))])),
+		(Value::from("hops"), Value::from(3)),
+	]));
+
+	let envelope: PreserveIgnoredFieldsDeep<Envelope, Fields> = from_msgpack(&data);
+	assert!(envelope.value.request.id == 7);
+	assert!(envelope.ignored_fields.len() == 2);
+	assert!(to_msgpack(&envelope) == data);
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "ron")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{AsRonStruct, FieldPath, KeepOrder, PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Team {
+	lead: Person,
+	size: u32,
+}
+
+fn ron<T: serde::de::DeserializeOwned + std::fmt::Debug>(data: &str) -> T {
+	let_assert!(Ok(value) = ron::from_str(data));
+	value
+}
+
+fn to_ron<T: serde::Serialize>(value: &T) -> String {
+	let_assert!(Ok(data) = ron::to_string(value));
+	data
+}
+
+#[test]
+fn round_trip_keeps_struct() {
+	let data = r#"(name:"Zohan",hobby:"hair dressing",colour:"red",glasses:false)"#;
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(data);
+	assert!(person.value == Person {
+		name: "Zohan".into(),
+		hobby: "hair dressing".into(),
+	});
+	assert!(person.ignored_fields.0.len() == 2);
+	assert!(person.ignored_fields.0.get(&ron::Value::from("colour")) == Some(&ron::Value::from("red")));
+	assert!(person.ignored_fields.0.get(&ron::Value::from("glasses")) == Some(&ron::Value::from(false)));
+
+	assert!(to_ron(&person) == r#"(name:"Zohan",hobby:"hair dressing",colour:"red",glasses:false)"#);
+}
+
+#[test]
+fn struct_without_ignored_fields() {
+	let data = r#"(name:"Zohan",hobby:"hair dressing")"#;
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(data);
+	assert!(person.ignored_fields.0.is_empty());
+	assert!(to_ron(&person) == data);
+}
+
+#[test]
+fn nested_values() {
+	let data = r#"(name:"Zohan",hobby:"hair dressing",friends:["Scrappy","Coco"],pet:{"kind":"dog"})"#;
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(data);
+	assert!(person.ignored_fields.0.len() == 2);
+	assert!(to_ron(&person) == data);
+}
+
+#[test]
+fn keep_order() {
+	let data = r#"(colour:"red",name:"Zohan",glasses:false,hobby:"hair dressing")"#;
+	let person: PreserveIgnoredFields<Person, AsRonStruct<KeepOrder<ron::Map>>> = ron(data);
+	assert!(to_ron(&person) == data);
+}
+
+#[test]
+fn deep() {
+	let data = r#"(lead:(name:"Zohan",hobby:"hair dressing",colour:"red"),size:3,mascot:"dog")"#;
+	let team: PreserveIgnoredFieldsDeep<Team, AsRonStruct<ron::Map>> = ron(data);
+	assert!(team.value.size == 3);
+	let_assert!(Some(lead) = team.ignored_fields.get(&FieldPath::from_iter(["lead"])));
+	assert!(lead.0.get(&ron::Value::from("colour")) == Some(&ron::Value::from("red")));
+
+	assert!(to_ron(&team) == data);
+}
+
+#[test]
+fn round_trip_struct_variant() {
+	#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+	enum Shape {
+		Point { x: i32, y: i32 },
+	}
+
+	let data = "Point(x:1,y:2,z:3)";
+	let shape: PreserveIgnoredFields<Shape, AsRonStruct<ron::Map>> = ron(data);
+	assert!(shape.value == Shape::Point { x: 1, y: 2 });
+	assert!(shape.ignored_fields.0.get(&ron::Value::from("z")) == Some(&ron::Value::Number(3u8.into())));
+	assert!(to_ron(&shape) == data);
+
+	let data = "Point(z:3,x:1,y:2)";
+	let shape: PreserveIgnoredFields<Shape, AsRonStruct<KeepOrder<ron::Map>>> = ron(data);
+	assert!(to_ron(&shape) == data);
+}
+
+#[test]
+fn deep_struct_variant() {
+	#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+	enum Role {
+		Member { person: Person },
+	}
+
+	let data = r#"{"role":Member(person:(name:"Zohan",hobby:"hair dressing",colour:"red"),since:2008)}"#;
+	let value: PreserveIgnoredFieldsDeep<std::collections::BTreeMap<String, Role>, AsRonStruct<ron::Map>> = ron(data);
+	assert!(value.ignored_fields.len() == 2);
+	assert!(to_ron(&value) == data);
+}
+
+#[test]
+fn pretty_round_trip() {
+	let data = r#"(name:"Zohan",hobby:"hair dressing",pet:{"kind":"dog"})"#;
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(data);
+	let_assert!(Ok(pretty) = ron::ser::to_string_pretty(&person, ron::ser::PrettyConfig::default()));
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(&pretty);
+	assert!(to_ron(&person) == data);
+}
+
+#[test]
+fn pretty_config_not_used_for_struct() {
+	let data = r#"(name:"Zohan",hobby:"hair dressing",colour:"red")"#;
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(data);
+	let_assert!(Ok(pretty) = ron::ser::to_string_pretty(&person, ron::ser::PrettyConfig::default()));
+	assert!(pretty == data);
+}
+
+#[test]
+fn without_wrapper_writes_map() {
+	let data = r#"(name:"Zohan",hobby:"hair dressing",colour:"red")"#;
+	let person: PreserveIgnoredFields<Person, ron::Map> = ron(data);
+	assert!(to_ron(&person) == r#"{"name":"Zohan","hobby":"hair dressing","colour":"red"}"#);
+}
+
+#[test]
+fn non_identifier_names() {
+	let person = PreserveIgnoredFields {
+		value: Person {
+			name: "Zohan".into(),
+			hobby: "hair dressing".into(),
+		},
+		ignored_fields: AsRonStruct(ron::Map::from_iter([("hair-colour", "black")])),
+	};
+	let data = to_ron(&person);
+	assert!(data == r#"(name:"Zohan",hobby:"hair dressing",r#hair-colour:"black")"#);
+
+	let person: PreserveIgnoredFields<Person, AsRonStruct<ron::Map>> = ron(&data);
+	assert!(person.ignored_fields.0.get(&ron::Value::from("hair-colour")) == Some(&ron::Value::from("black")));
+}
+
+#[test]
+fn non_string_keys_use_a_map() {
+	let person = PreserveIgnoredFields {
+		value: Person {
+			name: "Zohan".into(),
+			hobby: "hair dressing".into(),
+		},
+		ignored_fields: AsRonStruct(ron::Map::from_iter([(ron::Value::from(1), ron::Value::from("one"))])),
+	};
+	assert!(to_ron(&person) == r#"{"name":"Zohan","hobby":"hair dressing",1:"one"}"#);
+}
+
+#[test]
+fn serialize_value() {
+	let person = PreserveIgnoredFields {
+		value: Person {
+			name: "Zohan".into(),
+			hobby: "hair dressing".into(),
+		},
+		ignored_fields: AsRonStruct(ron::Value::Map(ron::Map::from_iter([("colour", "red")]))),
+	};
+	assert!(to_ron(&person) == r#"(name:"Zohan",hobby:"hair dressing",colour:"red")"#);
+
+	let person = PreserveIgnoredFields {
+		value: person.value,
+		ignored_fields: AsRonStruct(ron::Value::from("red")),
+	};
+	let_assert!(Err(e) = ron::to_string(&person));
+	assert!(e.to_string().contains("invalid type for ignored fields: expected a RON map, found a string"));
+}
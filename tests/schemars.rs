@@ -0,0 +1,91 @@
+#![cfg(all(feature = "schemars", feature = "serde_json"))]
+
+use assert2::{assert, let_assert};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, ObjectValidation, Schema, SchemaObject};
+use serde_ignored_fields::{PreserveIgnoredFields, WithMetadata};
+use std::collections::BTreeMap;
+
+/// A struct with a schema that does not allow additional properties, like `#[serde(deny_unknown_fields)]`.
+struct Person;
+
+impl schemars::JsonSchema for Person {
+	fn schema_name() -> String {
+		"Person".into()
+	}
+
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		let mut object = ObjectValidation::default();
+		object.properties.insert("name".into(), gen.subschema_for::<String>());
+		object.required.insert("name".into());
+		object.additional_properties = Some(Box::new(Schema::Bool(false)));
+		Schema::Object(SchemaObject {
+			instance_type: Some(InstanceType::Object.into()),
+			object: Some(Box::new(object)),
+			..Default::default()
+		})
+	}
+}
+
+fn additional_properties(schema: &Schema) -> Option<&Schema> {
+	let_assert!(Schema::Object(schema) = schema);
+	schema.object.as_ref()?.additional_properties.as_deref()
+}
+
+#[test]
+fn plain_type_denies_additional_properties() {
+	let schema = schemars::schema_for!(Person);
+	assert!(additional_properties(&schema.schema.into()) == Some(&Schema::Bool(false)));
+}
+
+#[test]
+fn any_value() {
+	let schema = schemars::schema_for!(PreserveIgnoredFields<Person, serde_json::Map<String, serde_json::Value>>);
+	let schema = Schema::Object(schema.schema);
+	assert!(additional_properties(&schema) == Some(&Schema::Bool(true)));
+
+	let_assert!(Ok(json) = serde_json::to_value(&schema));
+	assert!(json["additionalProperties"] == true);
+	assert!(json["properties"]["name"]["type"] == "string");
+	assert!(json["required"] == serde_json::json!(["name"]));
+}
+
+#[test]
+fn typed_value() {
+	let schema = schemars::schema_for!(PreserveIgnoredFields<Person, WithMetadata<BTreeMap<String, u32>>>);
+	let_assert!(Ok(json) = serde_json::to_value(Schema::Object(schema.schema)));
+	assert!(json["additionalProperties"]["type"] == "integer");
+	assert!(json["additionalProperties"]["format"] == "uint32");
+}
+
+#[test]
+fn field_of_struct() {
+	let mut gen = SchemaGenerator::default();
+	let schema = gen.subschema_for::<PreserveIgnoredFields<Person, BTreeMap<String, String>>>();
+	assert!(additional_properties(&schema) == Some(&gen.subschema_for::<String>()));
+
+	// The schema of `Person` itself is still available by name and is not modified.
+	let person = gen.subschema_for::<Person>();
+	let_assert!(Schema::Object(person) = person);
+	assert!(person.reference.as_deref() == Some("#/definitions/Person"));
+	let definition = &gen.definitions()["Person"];
+	assert!(additional_properties(definition) == Some(&Schema::Bool(false)));
+}
+
+fn any_value_for<U: serde_ignored_fields::JsonSchemaIgnoredFields>() -> bool {
+	let schema = schemars::schema_for!(PreserveIgnoredFields<Person, U>);
+	additional_properties(&Schema::Object(schema.schema)) == Some(&Schema::Bool(true))
+}
+
+#[test]
+fn other_formats() {
+	assert!(any_value_for::<Vec<(serde_ignored_fields::Value, serde_ignored_fields::Value)>>());
+	assert!(any_value_for::<bson::Document>());
+	assert!(any_value_for::<Vec<(ciborium::Value, ciborium::Value)>>());
+	assert!(any_value_for::<Vec<(rmpv::Value, rmpv::Value)>>());
+	assert!(any_value_for::<ron::Map>());
+
+	let schema = schemars::schema_for!(PreserveIgnoredFields<Person, Vec<(String, u32)>>);
+	let_assert!(Ok(json) = serde_json::to_value(Schema::Object(schema.schema)));
+	assert!(json["additionalProperties"]["type"] == "integer");
+}
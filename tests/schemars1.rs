@@ -0,0 +1,81 @@
+#![cfg(all(feature = "schemars1", feature = "serde_json"))]
+
+use assert2::{assert, let_assert};
+use schemars1::{Schema, SchemaGenerator};
+use serde_ignored_fields::{PreserveIgnoredFields, WithMetadata};
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// A struct with a schema that does not allow additional properties, like `#[serde(deny_unknown_fields)]`.
+struct Person;
+
+impl schemars1::JsonSchema for Person {
+	fn schema_name() -> Cow<'static, str> {
+		"Person".into()
+	}
+
+	fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+		let name = gen.subschema_for::<String>();
+		let_assert!(Ok(schema) = Schema::try_from(json!({
+			"type": "object",
+			"properties": {
+				"name": name,
+			},
+			"required": ["name"],
+			"additionalProperties": false,
+		})));
+		schema
+	}
+}
+
+#[test]
+fn plain_type_denies_additional_properties() {
+	let schema = schemars1::schema_for!(Person);
+	assert!(schema.get("additionalProperties") == Some(&json!(false)));
+}
+
+#[test]
+fn any_value() {
+	let schema = schemars1::schema_for!(PreserveIgnoredFields<Person, serde_json::Map<String, serde_json::Value>>);
+	assert!(schema.get("additionalProperties") == Some(&json!(true)));
+	assert!(schema.pointer("/properties/name/type") == Some(&json!("string")));
+	assert!(schema.get("required") == Some(&json!(["name"])));
+}
+
+#[test]
+fn typed_value() {
+	let schema = schemars1::schema_for!(PreserveIgnoredFields<Person, WithMetadata<BTreeMap<String, u32>>>);
+	assert!(schema.pointer("/additionalProperties/type") == Some(&json!("integer")));
+	assert!(schema.pointer("/additionalProperties/format") == Some(&json!("uint32")));
+}
+
+#[test]
+fn field_of_struct() {
+	let mut gen = SchemaGenerator::default();
+	let schema = gen.subschema_for::<PreserveIgnoredFields<Person, BTreeMap<String, String>>>();
+	assert!(schema.get("additionalProperties") == Some(gen.subschema_for::<String>().as_value()));
+
+	// The schema of `Person` itself is still available by name and is not modified.
+	let person = gen.subschema_for::<Person>();
+	assert!(person.get("$ref") == Some(&json!("#/$defs/Person")));
+	let definition = &gen.definitions()["Person"];
+	assert!(definition["additionalProperties"] == false);
+}
+
+fn any_value_for<U: serde_ignored_fields::JsonSchema1IgnoredFields>() -> bool {
+	let schema = schemars1::schema_for!(PreserveIgnoredFields<Person, U>);
+	schema.get("additionalProperties") == Some(&json!(true))
+}
+
+#[test]
+fn other_formats() {
+	assert!(any_value_for::<Vec<(serde_ignored_fields::Value, serde_ignored_fields::Value)>>());
+	assert!(any_value_for::<bson::Document>());
+	assert!(any_value_for::<Vec<(ciborium::Value, ciborium::Value)>>());
+	assert!(any_value_for::<Vec<(rmpv::Value, rmpv::Value)>>());
+	assert!(any_value_for::<ron::Map>());
+
+	let schema = schemars1::schema_for!(PreserveIgnoredFields<Person, Vec<(String, u32)>>);
+	assert!(schema.pointer("/additionalProperties/type") == Some(&json!("integer")));
+}
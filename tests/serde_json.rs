@@ -104,3 +104,25 @@ fn serialize_extra() {
 	}
 	"#).trim())
 }
+
+#[test]
+fn round_trip_many_distinct_keys() {
+	let mut ignored_fields = serde_json::Map::new();
+	for i in 0..10_000 {
+		ignored_fields.insert(format!("extra{i}"), i.into());
+	}
+	let zohan = PreserveIgnoredFields {
+		value: Person {
+			name: "Zohan".to_string(),
+			hobby: "hair-dressing".to_string(),
+		},
+		ignored_fields,
+	};
+
+	let_assert!(Ok(serialized) = ::serde_json::to_string(&zohan));
+	assert!(serialized.starts_with(r#"{"name":"Zohan","hobby":"hair-dressing","extra0":0,"extra1":1,"#));
+
+	let_assert!(Ok(deserialized) = json::<Person>(&serialized));
+	assert!(deserialized.value == zohan.value);
+	assert!(deserialized.ignored_fields == zohan.ignored_fields);
+}
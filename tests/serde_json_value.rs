@@ -0,0 +1,79 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+use serde_json::json;
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Team {
+	lead: Person,
+}
+
+#[test]
+fn round_trip() {
+	let data = r#"{"name":"Zohan","hobby":"hair dressing","colour":"red","pet":{"kind":"cat"}}"#;
+	let_assert!(Ok(person) = serde_json::from_str::<PreserveIgnoredFields<Person, serde_json::Value>>(data));
+	assert!(person.value.name == "Zohan");
+	assert!(person.value.hobby == "hair dressing");
+	assert!(person.ignored_fields == json!({"colour": "red", "pet": {"kind": "cat"}}));
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == data);
+}
+
+#[test]
+fn no_ignored_fields() {
+	let data = r#"{"name":"Zohan","hobby":"hair dressing"}"#;
+	let_assert!(Ok(person) = serde_json::from_str::<PreserveIgnoredFields<Person, serde_json::Value>>(data));
+	assert!(person.ignored_fields == serde_json::Value::Null);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&person));
+	assert!(serialized == data);
+}
+
+#[test]
+fn duplicate_field() {
+	let data = r#"{"name":"Zohan","hobby":"hair dressing","colour":"red","colour":"blue"}"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFields<Person, serde_json::Value>>(data));
+	assert!(e.to_string().starts_with("duplicate field: \"colour\""));
+}
+
+#[test]
+fn serialize_non_object() {
+	let person = PreserveIgnoredFields {
+		value: Person {
+			name: "Zohan".into(),
+			hobby: "hair dressing".into(),
+		},
+		ignored_fields: json!(["red"]),
+	};
+	let_assert!(Err(e) = serde_json::to_string(&person));
+	assert!(e.to_string() == "invalid type for ignored fields: expected a JSON object, found an array");
+}
+
+#[test]
+fn deserialize_into_non_object() {
+	let mut ignored_fields = json!("red");
+	let_assert!(Err(e) = serde_ignored_fields::DeserializeIgnoredFields::insert::<serde_json::Error>(
+		&mut ignored_fields,
+		"colour".into(),
+		json!("red"),
+	));
+	assert!(e.to_string() == r#"can not insert ignored field "colour" into a string: expected a JSON object"#);
+}
+
+#[test]
+fn deep() {
+	let data = r#"{"lead":{"name":"Zohan","hobby":"hair dressing","colour":"red"},"size":3}"#;
+	let_assert!(Ok(team) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Team, serde_json::Value>>(data));
+	assert!(team.ignored_fields.len() == 2);
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&team));
+	assert!(serialized == data);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "serde_yaml")]
+
+use assert2::{assert, let_assert};
+use indoc::indoc;
+use serde_ignored_fields::{FieldPath, IgnoredFieldNames, PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+	hobby: String,
+	colour: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Team {
+	lead: Person,
+	members: u32,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+enum Shape {
+	Circle { radius: u32 },
+}
+
+fn suggestions(names: &IgnoredFieldNames) -> Vec<(&str, Option<&'static str>)> {
+	names.suggestions().map(|(name, suggestion)| (name.as_str(), suggestion)).collect()
+}
+
+#[test]
+fn suggest_known_fields() {
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Person, IgnoredFieldNames>>(indoc!(
+		r#"
+			name: Zohan
+			nmae: Zohan
+			hobby: hair dressing
+			hobyy: hair dressing
+			color: red
+			colour: red
+			sparkles: true
+		"#
+	)));
+	assert!(deserialized.ignored_fields.known_fields == ["name", "hobby", "colour"]);
+	assert!(suggestions(&deserialized.ignored_fields) == [
+		("nmae", Some("name")),
+		("hobyy", Some("hobby")),
+		("color", Some("colour")),
+		("sparkles", None),
+	]);
+}
+
+#[test]
+fn suggest_known_fields_of_struct_variant() {
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Shape, IgnoredFieldNames>>(indoc!(
+		r#"
+			!Circle
+			radius: 4
+			raduis: 5
+		"#
+	)));
+	assert!(deserialized.ignored_fields.known_fields == ["radius"]);
+	assert!(suggestions(&deserialized.ignored_fields) == [("raduis", Some("radius"))]);
+}
+
+#[test]
+fn suggest_known_fields_of_nested_structs() {
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFieldsDeep<Team, IgnoredFieldNames>>(indoc!(
+		r#"
+			lead:
+				name: Zohan
+				hobby: hair dressing
+				colour: red
+				hoby: fighting
+			members: 3
+			member: 1
+		"#
+	).replace('\t', "  ").as_str()));
+	let root = &deserialized.ignored_fields[&FieldPath::new()];
+	let lead = &deserialized.ignored_fields[&FieldPath::from_iter(["lead"])];
+	assert!(suggestions(root) == [("member", Some("members"))]);
+	assert!(suggestions(lead) == [("hoby", Some("hobby"))]);
+}
@@ -0,0 +1,158 @@
+#![cfg(all(feature = "serde_json", feature = "serde_yaml"))]
+
+use assert2::{assert, check, let_assert};
+use serde_ignored_fields::{Map, PreserveIgnoredFields, Value};
+use serde_test::{Configure, Token};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Person {
+	name: String,
+}
+
+#[test]
+fn keep_exact_types() {
+	let value = PreserveIgnoredFields {
+		value: Person { name: "Zohan".into() },
+		ignored_fields: Map::from_iter([
+			("i8", Value::I8(-8)),
+			("u8", Value::U8(8)),
+			("f32", Value::F32(1.5)),
+			("char", Value::Char('z')),
+			("bytes", Value::Bytes(vec![1, 2, 3])),
+			("unit", Value::Unit),
+			("none", Value::None),
+			("some", Value::Some(Box::new(Value::U16(16)))),
+			("newtype", Value::Newtype(Box::new(Value::I64(-64)))),
+			("seq", Value::Seq(vec![Value::Bool(true)])),
+		]),
+	};
+
+	let tokens = [
+		Token::Map { len: Some(11) },
+		Token::Str("name"),
+		Token::Str("Zohan"),
+		Token::Str("i8"),
+		Token::I8(-8),
+		Token::Str("u8"),
+		Token::U8(8),
+		Token::Str("f32"),
+		Token::F32(1.5),
+		Token::Str("char"),
+		Token::Char('z'),
+		Token::Str("bytes"),
+		Token::Bytes(&[1, 2, 3]),
+		Token::Str("unit"),
+		Token::Unit,
+		Token::Str("none"),
+		Token::None,
+		Token::Str("some"),
+		Token::Some,
+		Token::U16(16),
+		Token::Str("newtype"),
+		Token::NewtypeStruct { name: "" },
+		Token::I64(-64),
+		Token::Str("seq"),
+		Token::Seq { len: Some(1) },
+		Token::Bool(true),
+		Token::SeqEnd,
+		Token::MapEnd,
+	];
+
+	serde_test::assert_ser_tokens(&value, &tokens);
+	serde_test::assert_de_tokens(&value.clone().readable(), &tokens);
+	serde_test::assert_de_tokens(&value.compact(), &tokens);
+}
+
+#[test]
+fn convert_between_formats() {
+	let_assert!(Ok(deserialized) = serde_yaml::from_str::<PreserveIgnoredFields<Person, Map>>(indoc::indoc!(r#"
+		name: Zohan
+		hobby: hair-dressing
+		friends:
+		- Scrappy
+		- Coco
+		age: 37
+	"#)));
+
+	assert!(deserialized.value.name == "Zohan");
+	assert!(deserialized.ignored_fields.len() == 3);
+	assert!(deserialized.ignored_fields.get("hobby") == Some(&Value::from("hair-dressing")));
+	assert!(deserialized.ignored_fields.get("age") == Some(&Value::U64(37)));
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == r#"{"name":"Zohan","hobby":"hair-dressing","friends":["Scrappy","Coco"],"age":37}"#);
+}
+
+#[test]
+fn error_on_duplicate_field() {
+	let data = r#"{"name":"Zohan","extra":1,"extra":2}"#;
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFields<Person, Map>>(data));
+	check!(e.to_string().contains(r#"duplicate field: "extra""#));
+}
+
+#[test]
+fn map_insert_and_remove() {
+	let mut map = Map::new();
+	assert!(map.insert("a", 1u32) == None);
+	assert!(map.insert("b", 2u32) == None);
+	assert!(map.insert("a", 3u32) == Some(Value::U32(1)));
+	assert!(map.keys().collect::<Vec<_>>() == [&Value::from("a"), &Value::from("b")]);
+	assert!(map.remove("a") == Some(Value::U32(3)));
+	assert!(map.len() == 1);
+	assert!(!map.contains_key("a"));
+}
+
+#[test]
+fn map_insert_integer_keys_by_value() {
+	let mut map = Map::new();
+	assert!(map.insert(Value::U8(1), "one") == None);
+	assert!(map.insert(Value::I64(1), "uno") == Some(Value::from("one")));
+	assert!(map.len() == 1);
+	assert!(map.keys().collect::<Vec<_>>() == [&Value::U8(1)]);
+}
+
+#[test]
+fn map_insert_after_remove() {
+	let mut map = Map::from_iter([("a", 1u32), ("b", 2u32), ("c", 3u32)]);
+	assert!(map.remove("a") == Some(Value::U32(1)));
+	assert!(map.insert("c", 4u32) == Some(Value::U32(3)));
+	assert!(map.insert("a", 5u32) == None);
+	assert!(map.keys().collect::<Vec<_>>() == [&Value::from("b"), &Value::from("c"), &Value::from("a")]);
+	assert!(map.get("c") == Some(&Value::U32(4)));
+}
+
+#[test]
+fn many_ignored_fields() {
+	let mut data = String::from(r#"{"name":"Zohan""#);
+	for i in 0..10_000 {
+		data += &format!(r#","field{i}":{i}"#);
+	}
+	data += "}";
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Person, Map>>(&data));
+	assert!(deserialized.ignored_fields.len() == 10_000);
+	assert!(deserialized.ignored_fields.get("field1234") == Some(&Value::U64(1234)));
+
+	let data = data.replace("field9999", "field0");
+	let_assert!(Err(e) = serde_json::from_str::<PreserveIgnoredFields<Person, Map>>(&data));
+	check!(e.to_string().contains(r#"duplicate field: "field0""#));
+}
+
+#[test]
+fn duplicate_integer_field() {
+	let mut map = Map::new();
+	let_assert!(Ok(()) = serde_ignored_fields::DeserializeIgnoredFields::insert::<serde_json::Error>(&mut map, Value::U8(7), Value::Unit));
+	let_assert!(Err(e) = serde_ignored_fields::DeserializeIgnoredFields::insert::<serde_json::Error>(&mut map, Value::I32(7), Value::Unit));
+	assert!(e.to_string() == "duplicate field: I32(7)");
+}
+
+#[test]
+fn map_lookup_integer_keys_by_value() {
+	let mut map = Map::new();
+	assert!(map.insert(Value::U8(1), "one") == None);
+	assert!(map.get(&Value::U64(1)) == Some(&Value::from("one")));
+	assert!(map.contains_key(&Value::I32(1)));
+	assert!(let Some(Value::String(_)) = map.get_mut(&Value::U16(1)));
+	assert!(map.get(&Value::U64(2)) == None);
+	assert!(map.remove(&Value::I128(1)) == Some(Value::from("one")));
+	assert!(map.is_empty());
+}
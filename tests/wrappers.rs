@@ -0,0 +1,135 @@
+#![cfg(feature = "serde_json")]
+
+use assert2::{assert, let_assert};
+use serde_ignored_fields::{Map, PreserveIgnoredFields, PreserveIgnoredFieldsDeep};
+use serde_test::{assert_ser_tokens, Token};
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Config {
+	name: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Newtype(Config);
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+struct Transparent {
+	config: Config,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Unit;
+
+fn config() -> Config {
+	Config { name: "Zohan".into() }
+}
+
+fn colour() -> Map {
+	Map::from_iter([("colour", "red")])
+}
+
+#[test]
+fn round_trip_none() {
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Option<Config>, Map>>("null"));
+	assert!(deserialized.value == None);
+	assert!(deserialized.ignored_fields.is_empty());
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == "null");
+
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFieldsDeep<Option<Config>, Map>>("null"));
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == "null");
+}
+
+#[test]
+fn round_trip_some() {
+	let data = r#"{"name":"Zohan","colour":"red"}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Option<Config>, Map>>(data));
+	assert!(deserialized.value == Some(config()));
+	assert!(deserialized.ignored_fields == colour());
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+
+	assert_ser_tokens(&deserialized, &[
+		Token::Some,
+		Token::Map { len: Some(2) },
+		Token::Str("name"),
+		Token::Str("Zohan"),
+		Token::Str("colour"),
+		Token::Str("red"),
+		Token::MapEnd,
+	]);
+}
+
+#[test]
+fn none_with_ignored_fields() {
+	let value = PreserveIgnoredFields {
+		value: None::<Config>,
+		ignored_fields: colour(),
+	};
+	let_assert!(Err(e) = serde_json::to_string(&value));
+	assert!(e.to_string() == "invalid type `none`: can only re-serialize a map or struct with ignored fields");
+}
+
+#[test]
+fn round_trip_newtype_struct() {
+	let data = r#"{"name":"Zohan","colour":"red"}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Newtype, Map>>(data));
+	assert!(deserialized.value == Newtype(config()));
+	assert!(deserialized.ignored_fields == colour());
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+
+	assert_ser_tokens(&deserialized, &[
+		Token::NewtypeStruct { name: "Newtype" },
+		Token::Map { len: Some(2) },
+		Token::Str("name"),
+		Token::Str("Zohan"),
+		Token::Str("colour"),
+		Token::Str("red"),
+		Token::MapEnd,
+	]);
+}
+
+#[test]
+fn round_trip_transparent() {
+	let data = r#"{"name":"Zohan","colour":"red"}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Transparent, Map>>(data));
+	assert!(deserialized.value == Transparent { config: config() });
+	assert!(deserialized.ignored_fields == colour());
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn round_trip_optional_newtype() {
+	let data = r#"{"name":"Zohan","colour":"red"}"#;
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Option<Newtype>, Map>>(data));
+	assert!(deserialized.value == Some(Newtype(config())));
+
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == data);
+}
+
+#[test]
+fn round_trip_unit() {
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<(), Map>>("null"));
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == "null");
+
+	let_assert!(Ok(deserialized) = serde_json::from_str::<PreserveIgnoredFields<Unit, Map>>("null"));
+	let_assert!(Ok(serialized) = serde_json::to_string(&deserialized));
+	assert!(serialized == "null");
+
+	let value = PreserveIgnoredFields {
+		value: Unit,
+		ignored_fields: colour(),
+	};
+	let_assert!(Err(e) = serde_json::to_string(&value));
+	assert!(e.to_string() == "invalid type `unit struct`: can only re-serialize a map or struct with ignored fields");
+}